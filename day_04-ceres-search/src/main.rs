@@ -1,43 +1,122 @@
+use std::collections::HashMap;
 use std::fs;
 use std::io::Result;
 use std::path::Path;
 use std::env;
+use std::str::FromStr;
 
 struct Matrix {
     data: Vec<Vec<char>>,
     size: (usize, usize),
+    char_index: HashMap<char, Vec<(usize, usize)>>,
 }
 
-// TODO: Définir un itérateur pour parcourir les indices et les valeurs d'une matrice : (rows, cols), char
+/// Comportement de la recherche lorsqu'un motif dépasse les bords de la matrice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BoundaryMode {
+    /// Les cases hors de la matrice ne correspondent jamais
+    Strict,
+    /// La matrice est un tore : on repart du bord opposé
+    Wrap,
+    /// La matrice est réfléchie sur ses bords
+    Mirror,
+}
+
+impl BoundaryMode {
+    /// Ramène un indice éventuellement hors limites dans `0..len`
+    fn resolve(self, index: isize, len: usize) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        let len = len as isize;
+        match self {
+            BoundaryMode::Strict => (0..len).contains(&index).then_some(index as usize),
+            BoundaryMode::Wrap => Some(index.rem_euclid(len) as usize),
+            BoundaryMode::Mirror => {
+                let folded = index.rem_euclid(2 * len);
+                let reflected = if folded >= len { 2 * len - 1 - folded } else { folded };
+                Some(reflected as usize)
+            }
+        }
+    }
+}
+
+impl FromStr for BoundaryMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(BoundaryMode::Strict),
+            "wrap" => Ok(BoundaryMode::Wrap),
+            "mirror" => Ok(BoundaryMode::Mirror),
+            _ => Err(format!("Unknown boundary mode: {} (expected strict, wrap or mirror)", s)),
+        }
+    }
+}
 
 impl Matrix {
     fn new(data: Vec<Vec<char>>) -> Self {
         let rows = data.len();
         let cols = if rows > 0 { data[0].len() } else { 0 };
-        Matrix { data, size: (rows, cols) }
+        let mut matrix = Matrix { data, size: (rows, cols), char_index: HashMap::new() };
+        let mut char_index: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
+        for (row, col, &ch) in matrix.cells() {
+            char_index.entry(ch).or_default().push((row, col));
+        }
+        matrix.char_index = char_index;
+        matrix
+    }
+
+    /// Parcourt les indices et les valeurs de la matrice : (row, col, char)
+    fn cells(&self) -> impl Iterator<Item = (usize, usize, &char)> {
+        self.data
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| line.iter().enumerate().map(move |(col, ch)| (row, col, ch)))
+    }
+
+    /// Nombre d'occurrences d'un caractère dans la matrice
+    fn char_frequency(&self, ch: char) -> usize {
+        self.char_index.get(&ch).map_or(0, |positions| positions.len())
+    }
+
+    /// Choisit le caractère d'ancrage d'un motif : le plus rare dans la matrice.
+    /// Renvoie la position de l'ancre dans le motif, ou None si le motif est vide.
+    fn anchor_for(&self, pattern: &Matrix) -> Option<(usize, usize)> {
+        let mut anchor: Option<((usize, usize), usize)> = None;
+        for (i, j, &ch) in pattern.cells() {
+            if ch == ' ' {
+                continue;
+            }
+            let frequency = self.char_frequency(ch);
+            if anchor.is_none_or(|(_, best)| frequency < best) {
+                anchor = Some(((i, j), frequency));
+            }
+        }
+        anchor.map(|(position, _)| position)
     }
 
     /// Vérifie si un motif correspond à une position donnée
-    fn matches_pattern(&self, pattern: &Matrix, center_row: usize, center_col: usize) -> bool {
+    fn matches_pattern(&self, pattern: &Matrix, center_row: isize, center_col: isize, mode: BoundaryMode) -> bool {
         let pattern_center_row = pattern.size.0 as isize / 2;
         let pattern_center_col = pattern.size.1 as isize / 2;
 
-        for (i, row) in pattern.data.iter().enumerate() {
-            for (j, &ch) in row.iter().enumerate() {
-                if ch != ' ' { // Seuls les caractères significatifs sont vérifiés
-                    let target_row = center_row as isize + i as isize - pattern_center_row;
-                    let target_col = center_col as isize + j as isize - pattern_center_col;
-
-                    // Vérification des limites
-                    if target_row < 0 || target_col < 0 || 
-                        target_row >= self.size.0 as isize || target_col >= self.size.1 as isize {
-                        return false;
-                    }
-
-                    // Vérification du caractère
-                    if self.data[target_row as usize][target_col as usize] != ch {
-                        return false;
-                    }
+        for (i, j, &ch) in pattern.cells() {
+            if ch != ' ' { // Seuls les caractères significatifs sont vérifiés
+                let target_row = center_row + i as isize - pattern_center_row;
+                let target_col = center_col + j as isize - pattern_center_col;
+
+                // Vérification des limites selon le mode choisi
+                let (Some(target_row), Some(target_col)) = (
+                    mode.resolve(target_row, self.size.0),
+                    mode.resolve(target_col, self.size.1),
+                ) else {
+                    return false;
+                };
+
+                // Vérification du caractère
+                if self.data[target_row].get(target_col) != Some(&ch) {
+                    return false;
                 }
             }
         }
@@ -45,24 +124,31 @@ impl Matrix {
     }
 
     /// Compte le nombre de correspondances pour un ensemble de motifs
-    fn count_pattern_matches(&self, patterns: &[Matrix]) -> usize {
-        let mut count = 0;
-
-        for row in 0..self.size.0 {
-            for col in 0..self.size.1 {
-                // Filtrer sur le caractère central du premier motif (optimisation)
-                let central_char = patterns[0].data[patterns[0].size.0 / 2][patterns[0].size.1 / 2];
-                if self.data[row][col] == central_char {
-                    // Tester chaque motif à la position donnée
-                    for pattern in patterns {
-                        if self.matches_pattern(pattern, row, col) {
-                            count += 1;
-                        }
-                    }
-                }
-            }
-        }
-        count
+    fn count_pattern_matches(&self, patterns: &[Matrix], mode: BoundaryMode) -> usize {
+        patterns.iter().map(|pattern| self.count_single_pattern(pattern, mode)).sum()
+    }
+
+    /// Compte les correspondances d'un motif en ne testant que les cases
+    /// contenant son caractère d'ancrage
+    fn count_single_pattern(&self, pattern: &Matrix, mode: BoundaryMode) -> usize {
+        let Some((anchor_row, anchor_col)) = self.anchor_for(pattern) else {
+            return 0;
+        };
+        let anchor_char = pattern.data[anchor_row][anchor_col];
+        let Some(positions) = self.char_index.get(&anchor_char) else {
+            return 0;
+        };
+
+        // Décalage entre l'ancre et le centre du motif
+        let offset_row = pattern.size.0 as isize / 2 - anchor_row as isize;
+        let offset_col = pattern.size.1 as isize / 2 - anchor_col as isize;
+
+        positions
+            .iter()
+            .filter(|&&(row, col)| {
+                self.matches_pattern(pattern, row as isize + offset_row, col as isize + offset_col, mode)
+            })
+            .count()
     }
 }
 
@@ -86,6 +172,110 @@ fn string_to_matrix(input_file: String) -> Matrix {
     Matrix::new(data)
 }
 
+/// Motifs de la partie 1 : XMAS dans les huit directions
+fn xmas_patterns() -> Vec<Matrix> {
+    vec![
+        Matrix::new(vec![
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'X', 'M', 'A', 'S'],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+        ]),
+        Matrix::new(vec![
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec!['S', 'A', 'M', 'X', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+        ]),
+        Matrix::new(vec![
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'M', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'A', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'S', ' ', ' ', ' '],
+        ]),
+        Matrix::new(vec![
+            vec![' ', ' ', ' ', 'S', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'A', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'M', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+        ]),
+        Matrix::new(vec![
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
+            vec![' ', ' ', 'M', ' ', ' ', ' ', ' '],
+            vec![' ', 'A', ' ', ' ', ' ', ' ', ' '],
+            vec!['S', ' ', ' ', ' ', ' ', ' ', ' '],
+        ]),
+        Matrix::new(vec![
+            vec![' ', ' ', ' ', ' ', ' ', ' ', 'S'],
+            vec![' ', ' ', ' ', ' ', ' ', 'A', ' '],
+            vec![' ', ' ', ' ', ' ', 'M', ' ', ' '],
+            vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+        ]),
+        Matrix::new(vec![
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', 'M', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', 'A', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', 'S'],
+        ]),
+        Matrix::new(vec![
+            vec!['S', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', 'A', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', 'M', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+            vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
+        ]),
+    ]
+}
+
+/// Motifs de la partie 2 : deux MAS en croix
+fn x_mas_patterns() -> Vec<Matrix> {
+    vec![
+        Matrix::new(vec![
+            vec!['M', ' ', 'M'],
+            vec![' ', 'A', ' '],
+            vec!['S', ' ', 'S'],
+        ]),
+        Matrix::new(vec![
+            vec!['S', ' ', 'S'],
+            vec![' ', 'A', ' '],
+            vec!['M', ' ', 'M'],
+        ]),
+        Matrix::new(vec![
+            vec!['M', ' ', 'S'],
+            vec![' ', 'A', ' '],
+            vec!['M', ' ', 'S'],
+        ]),
+        Matrix::new(vec![
+            vec!['S', ' ', 'M'],
+            vec![' ', 'A', ' '],
+            vec!['S', ' ', 'M'],
+        ]),
+    ]
+}
+
 fn main() {
     let contents = match get_aoc_input("input.txt") {  // 'match' handles the result of file_parser
         Ok(contents) => contents,
@@ -95,106 +285,135 @@ fn main() {
         },
     };
 
+    // Mode de gestion des bords optionnel : strict (par défaut), wrap ou mirror
+    let mode = match env::args().nth(1).map(|arg| arg.parse::<BoundaryMode>()) {
+        None => BoundaryMode::Strict,
+        Some(Ok(mode)) => mode,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            return;
+        },
+    };
+
     let word_search: Matrix = string_to_matrix(contents);
 
-    let mut paterns_1 = vec![];
-    paterns_1.push(Matrix::new(vec![
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'X', 'M', 'A', 'S'],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-    ]));
-    paterns_1.push(Matrix::new(vec![
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec!['S', 'A', 'M', 'X', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-    ]));
-    paterns_1.push(Matrix::new(vec![
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'M', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'A', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'S', ' ', ' ', ' '],
-    ]));
-    paterns_1.push(Matrix::new(vec![
-        vec![' ', ' ', ' ', 'S', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'A', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'M', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-    ]));
-    paterns_1.push(Matrix::new(vec![
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
-        vec![' ', ' ', 'M', ' ', ' ', ' ', ' '],
-        vec![' ', 'A', ' ', ' ', ' ', ' ', ' '],
-        vec!['S', ' ', ' ', ' ', ' ', ' ', ' '],
-    ]));
-    paterns_1.push(Matrix::new(vec![
-        vec![' ', ' ', ' ', ' ', ' ', ' ', 'S'],
-        vec![' ', ' ', ' ', ' ', ' ', 'A', ' '],
-        vec![' ', ' ', ' ', ' ', 'M', ' ', ' '],
-        vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-    ]));
-    paterns_1.push(Matrix::new(vec![
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', 'M', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', 'A', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', 'S'],
-    ]));
-    paterns_1.push(Matrix::new(vec![
-        vec!['S', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', 'A', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', 'M', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', 'X', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-        vec![' ', ' ', ' ', ' ', ' ', ' ', ' '],
-    ]));
-
-    let mut paterns_2 = vec![];
-    paterns_2.push(Matrix::new(vec![
-        vec!['M', ' ', 'M'],
-        vec![' ', 'A', ' '],
-        vec!['S', ' ', 'S'],
-    ]));
-    paterns_2.push(Matrix::new(vec![
-        vec!['S', ' ', 'S'],
-        vec![' ', 'A', ' '],
-        vec!['M', ' ', 'M'],
-    ]));
-    paterns_2.push(Matrix::new(vec![
-        vec!['M', ' ', 'S'],
-        vec![' ', 'A', ' '],
-        vec!['M', ' ', 'S'],
-    ]));
-    paterns_2.push(Matrix::new(vec![
-        vec!['S', ' ', 'M'],
-        vec![' ', 'A', ' '],
-        vec!['S', ' ', 'M'],
-    ]));
-
-    let match_count_1 = word_search.count_pattern_matches(&paterns_1);
-    let match_count_2 = word_search.count_pattern_matches(&paterns_2);
+    let paterns_1 = xmas_patterns();
+    let paterns_2 = x_mas_patterns();
+
+    let match_count_1 = word_search.count_pattern_matches(&paterns_1, mode);
+    let match_count_2 = word_search.count_pattern_matches(&paterns_2, mode);
     println!("Nombre de correspondances trouvées: {}", match_count_1);
     println!("Nombre de correspondances trouvées: {}", match_count_2);
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    #[test]
+    fn test_count_pattern_matches() {
+        let word_search = string_to_matrix(EXAMPLE.to_string());
+        assert_eq!(word_search.count_pattern_matches(&xmas_patterns(), BoundaryMode::Strict), 18);
+        assert_eq!(word_search.count_pattern_matches(&x_mas_patterns(), BoundaryMode::Strict), 9);
+    }
+
+    #[test]
+    fn test_count_pattern_matches_with_different_centres() {
+        let word_search = string_to_matrix(EXAMPLE.to_string());
+        // Centre 'A' pour le premier motif, 'M' pour le second
+        let patterns = vec![
+            Matrix::new(vec![vec!['X', 'M', 'A', 'S']]),
+            Matrix::new(vec![vec!['S', 'A', 'M', 'X']]),
+        ];
+        let forward = word_search.count_pattern_matches(&patterns[..1], BoundaryMode::Strict);
+        let backward = word_search.count_pattern_matches(&patterns[1..], BoundaryMode::Strict);
+        assert_eq!(forward, 3);
+        assert_eq!(backward, 2);
+        assert_eq!(word_search.count_pattern_matches(&patterns, BoundaryMode::Strict), forward + backward);
+    }
+
+    #[test]
+    fn test_count_pattern_matches_with_blank_centre() {
+        let word_search = string_to_matrix("XMAS\n....".to_string());
+        // Le centre du motif est un blanc qui tombe hors de la matrice
+        let pattern = Matrix::new(vec![
+            vec!['X', 'M', 'A', 'S', ' ', ' ', ' '],
+        ]);
+        assert_eq!(word_search.count_pattern_matches(&[pattern], BoundaryMode::Strict), 1);
+    }
+
+    #[test]
+    fn test_count_pattern_matches_empty() {
+        let word_search = string_to_matrix(EXAMPLE.to_string());
+        assert_eq!(word_search.count_pattern_matches(&[], BoundaryMode::Strict), 0);
+        let blank = Matrix::new(vec![vec![' ', ' ', ' ']]);
+        assert_eq!(word_search.count_pattern_matches(&[blank], BoundaryMode::Strict), 0);
+    }
+
+    #[test]
+    fn test_cells() {
+        let matrix = Matrix::new(vec![vec!['a', 'b'], vec!['c', 'd']]);
+        let cells: Vec<(usize, usize, char)> = matrix.cells().map(|(r, c, &ch)| (r, c, ch)).collect();
+        assert_eq!(cells, vec![(0, 0, 'a'), (0, 1, 'b'), (1, 0, 'c'), (1, 1, 'd')]);
+    }
+
+    #[test]
+    fn test_boundary_mode_resolve() {
+        assert_eq!(BoundaryMode::Strict.resolve(-1, 4), None);
+        assert_eq!(BoundaryMode::Strict.resolve(4, 4), None);
+        assert_eq!(BoundaryMode::Strict.resolve(2, 4), Some(2));
+        assert_eq!(BoundaryMode::Wrap.resolve(-1, 4), Some(3));
+        assert_eq!(BoundaryMode::Wrap.resolve(9, 4), Some(1));
+        assert_eq!(BoundaryMode::Mirror.resolve(-1, 4), Some(0));
+        assert_eq!(BoundaryMode::Mirror.resolve(4, 4), Some(3));
+        assert_eq!(BoundaryMode::Mirror.resolve(-5, 4), Some(3));
+        assert_eq!(BoundaryMode::Wrap.resolve(0, 0), None);
+    }
+
+    #[test]
+    fn test_count_pattern_matches_wrap() {
+        // "XMAS" n'apparaît qu'en passant par le bord droit
+        let word_search = string_to_matrix("ASXM\n....".to_string());
+        let pattern = Matrix::new(vec![vec!['X', 'M', 'A', 'S']]);
+        assert_eq!(word_search.count_pattern_matches(std::slice::from_ref(&pattern), BoundaryMode::Strict), 0);
+        assert_eq!(word_search.count_pattern_matches(std::slice::from_ref(&pattern), BoundaryMode::Wrap), 1);
+    }
+
+    #[test]
+    fn test_count_pattern_matches_mirror() {
+        // "XMAS" se lit en repartant du bord droit dans l'autre sens
+        let word_search = string_to_matrix("..XMA\n.....".to_string());
+        let pattern = Matrix::new(vec![vec!['X', 'M', 'A', 'A', 'M']]);
+        assert_eq!(word_search.count_pattern_matches(std::slice::from_ref(&pattern), BoundaryMode::Strict), 0);
+        assert_eq!(word_search.count_pattern_matches(std::slice::from_ref(&pattern), BoundaryMode::Mirror), 1);
+    }
+
+    #[test]
+    fn test_boundary_mode_from_str() {
+        assert_eq!("wrap".parse::<BoundaryMode>(), Ok(BoundaryMode::Wrap));
+        assert!("torus".parse::<BoundaryMode>().is_err());
+    }
+
+    #[test]
+    fn test_anchor_is_rarest_char() {
+        let word_search = string_to_matrix(EXAMPLE.to_string());
+        let pattern = Matrix::new(vec![vec!['X', 'M', 'A', 'S']]);
+        let anchor = word_search.anchor_for(&pattern).unwrap();
+        let anchor_char = pattern.data[anchor.0][anchor.1];
+        for ch in ['X', 'M', 'A', 'S'] {
+            assert!(word_search.char_frequency(anchor_char) <= word_search.char_frequency(ch));
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_05::{build_dependency_graph, is_valid_update, RuleMatrix};
+
+const PAGES: u32 = 1_000;
+const RULES: usize = 10_000;
+const UPDATES: usize = 100_000;
+
+// Générateur pseudo-aléatoire (LCG) pour des entrées reproductibles
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u32) -> u32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % bound as u64) as u32
+    }
+}
+
+fn synthetic_input() -> (Vec<(u32, u32)>, Vec<Vec<u32>>) {
+    let mut rng = Lcg(2024);
+    let rules = (0..RULES)
+        .map(|_| loop {
+            let (x, y) = (rng.next(PAGES), rng.next(PAGES));
+            if x != y {
+                break (x.min(y), x.max(y));
+            }
+        })
+        .collect();
+    let updates = (0..UPDATES)
+        .map(|_| {
+            let len = 5 + 2 * rng.next(10) as usize;
+            let mut update: Vec<u32> = (0..len).map(|_| rng.next(PAGES)).collect();
+            // Une mise à jour sur deux est triée, donc valide
+            if rng.next(2) == 0 {
+                update.sort_unstable();
+            }
+            update
+        })
+        .collect();
+    (rules, updates)
+}
+
+fn benchmark_is_valid_update(c: &mut Criterion) {
+    let (rules, updates) = synthetic_input();
+    let graph = build_dependency_graph(&rules);
+    let rule_matrix = RuleMatrix::new(&graph);
+
+    let mut group = c.benchmark_group("is_valid_update");
+    group.sample_size(10);
+    group.bench_function("graph", |b| b.iter(|| {
+        black_box(updates.iter().filter(|update| is_valid_update(update, &graph)).count());
+    }));
+    group.bench_function("rule_matrix", |b| b.iter(|| {
+        black_box(updates.iter().filter(|update| rule_matrix.is_valid_update(update)).count());
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_is_valid_update);
+criterion_main!(benches);
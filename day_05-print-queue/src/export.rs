@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::rules::validate_update;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Dot,
+    Mermaid,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(ExportFormat::Dot),
+            "mermaid" => Ok(ExportFormat::Mermaid),
+            _ => Err(format!("Unknown export format: {} (expected dot or mermaid)", s)),
+        }
+    }
+}
+
+struct Node {
+    page: u32,
+    label: String,
+}
+
+struct Edge {
+    from: u32,
+    to: u32,
+    violated: bool,
+}
+
+/// Graphe prêt à être exporté : sommets, arêtes et légende éventuelle
+struct ExportGraph {
+    name: &'static str,
+    caption: Vec<String>,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+impl ExportGraph {
+    fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Dot => self.to_dot(),
+            ExportFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "digraph {} {{", self.name).unwrap();
+        if !self.caption.is_empty() {
+            writeln!(out, "    label=\"{}\";", self.caption.join("\\n")).unwrap();
+        }
+        for node in &self.nodes {
+            writeln!(out, "    \"{}\" [label=\"{}\"];", node.page, node.label.replace('\n', "\\n")).unwrap();
+        }
+        for edge in &self.edges {
+            if edge.violated {
+                writeln!(out, "    \"{}\" -> \"{}\" [color=red];", edge.from, edge.to).unwrap();
+            } else {
+                writeln!(out, "    \"{}\" -> \"{}\";", edge.from, edge.to).unwrap();
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_mermaid(&self) -> String {
+        let mut out = String::new();
+        // Mermaid n'a pas de légende : on l'écrit en commentaire
+        for line in &self.caption {
+            writeln!(out, "%% {}", line).unwrap();
+        }
+        out.push_str("flowchart LR\n");
+        for node in &self.nodes {
+            writeln!(out, "    p{}[\"{}\"]", node.page, node.label.replace('\n', "<br/>")).unwrap();
+        }
+        for edge in &self.edges {
+            writeln!(out, "    p{} --> p{}", edge.from, edge.to).unwrap();
+        }
+        for (i, _) in self.edges.iter().enumerate().filter(|(_, edge)| edge.violated) {
+            writeln!(out, "    linkStyle {} stroke:red;", i).unwrap();
+        }
+        out
+    }
+}
+
+fn join_pages(pages: &[u32]) -> String {
+    pages.iter().map(|page| page.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Exporte le graphe global des règles
+pub fn export_rules(graph: &HashMap<u32, HashSet<u32>>, format: ExportFormat) -> String {
+    let mut edges: Vec<Edge> = graph.iter()
+        .flat_map(|(&from, dependencies)| dependencies.iter().map(move |&to| Edge { from, to, violated: false }))
+        .collect();
+    edges.sort_by_key(|edge| (edge.from, edge.to));
+
+    let mut pages: Vec<u32> = edges.iter().flat_map(|edge| [edge.from, edge.to]).collect();
+    pages.sort_unstable();
+    pages.dedup();
+
+    ExportGraph {
+        name: "rules",
+        caption: Vec::new(),
+        nodes: pages.into_iter().map(|page| Node { page, label: page.to_string() }).collect(),
+        edges,
+    }
+    .render(format)
+}
+
+/// Exporte le sous-graphe des règles d'une mise à jour : les règles violées sont en rouge
+/// et chaque page est annotée avec sa position avant et après correction
+pub fn export_update(update: &[u32], graph: &HashMap<u32, HashSet<u32>>, format: ExportFormat) -> String {
+    let report = validate_update(update, graph);
+    let violated: HashSet<(u32, u32)> = report.violations.iter().map(|violation| violation.rule).collect();
+    let pages: HashSet<u32> = update.iter().copied().collect();
+
+    let mut caption = vec![format!("update: {}", join_pages(update))];
+    let corrected_positions: HashMap<u32, usize> = match &report.corrected {
+        Ok(corrected) => {
+            caption.push(format!("corrected: {}", join_pages(corrected)));
+            corrected.iter().enumerate().map(|(i, &page)| (page, i)).collect()
+        }
+        Err(e) => {
+            caption.push(format!("cannot correct update: {}", e));
+            HashMap::new()
+        }
+    };
+
+    let nodes = update.iter()
+        .enumerate()
+        .map(|(i, &page)| {
+            let label = match corrected_positions.get(&page) {
+                Some(&j) if i != j => format!("{}\n#{} → #{}", page, i, j),
+                _ => format!("{}\n#{}", page, i),
+            };
+            Node { page, label }
+        })
+        .collect();
+
+    let mut edges: Vec<Edge> = update.iter()
+        .filter_map(|from| graph.get(from).map(|dependencies| (*from, dependencies)))
+        .flat_map(|(from, dependencies)| {
+            dependencies.iter()
+                .filter(|to| pages.contains(to))
+                .map(move |&to| Edge { from, to, violated: false })
+        })
+        .collect();
+    edges.sort_by_key(|edge| (edge.from, edge.to));
+    for edge in &mut edges {
+        edge.violated = violated.contains(&(edge.from, edge.to));
+    }
+
+    ExportGraph { name: "update", caption, nodes, edges }.render(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::build_dependency_graph;
+
+    #[test]
+    fn test_export_rules() {
+        let graph = build_dependency_graph(&[(47, 53), (97, 47), (97, 53)]);
+        assert_eq!(
+            export_rules(&graph, ExportFormat::Dot),
+            "digraph rules {\n    \"47\" [label=\"47\"];\n    \"53\" [label=\"53\"];\n    \"97\" [label=\"97\"];\n    \
+             \"47\" -> \"53\";\n    \"97\" -> \"47\";\n    \"97\" -> \"53\";\n}\n"
+        );
+        assert_eq!(
+            export_rules(&graph, ExportFormat::Mermaid),
+            "flowchart LR\n    p47[\"47\"]\n    p53[\"53\"]\n    p97[\"97\"]\n    \
+             p47 --> p53\n    p97 --> p47\n    p97 --> p53\n"
+        );
+    }
+
+    #[test]
+    fn test_export_update() {
+        let graph = build_dependency_graph(&[(97, 75), (75, 47), (97, 47)]);
+        let dot = export_update(&[75, 97, 47], &graph, ExportFormat::Dot);
+        assert!(dot.contains("label=\"update: 75, 97, 47\\ncorrected: 97, 75, 47\";"));
+        assert!(dot.contains("\"75\" [label=\"75\\n#0 → #1\"];"));
+        assert!(dot.contains("\"47\" [label=\"47\\n#2\"];"));
+        assert!(dot.contains("\"97\" -> \"75\" [color=red];"));
+        assert!(dot.contains("\"75\" -> \"47\";"));
+
+        let mermaid = export_update(&[75, 97, 47], &graph, ExportFormat::Mermaid);
+        assert!(mermaid.starts_with("%% update: 75, 97, 47\n%% corrected: 97, 75, 47\nflowchart LR\n"));
+        // Arêtes triées : 75 --> 47, 97 --> 47, 97 --> 75 (la troisième est violée)
+        assert!(mermaid.ends_with("    p75 --> p47\n    p97 --> p47\n    p97 --> p75\n    linkStyle 2 stroke:red;\n"));
+    }
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!("mermaid".parse::<ExportFormat>(), Ok(ExportFormat::Mermaid));
+        assert!("svg".parse::<ExportFormat>().is_err());
+    }
+}
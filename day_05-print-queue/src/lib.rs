@@ -0,0 +1,12 @@
+pub mod export;
+pub mod parser;
+pub mod rule_set;
+pub mod rules;
+
+pub use export::{export_rules, export_update, ExportFormat};
+pub use parser::{parse_input, parse_rules, parse_updates, Input, ParseError};
+pub use rule_set::{RuleSet, StatusChange};
+pub use rules::{
+    build_dependency_graph, correct_update_order, find_cycles, find_rule_cycles, find_violations, format_cycle,
+    is_valid_update, validate_update, OrderingError, RuleMatrix, ValidationReport, Violation,
+};
\ No newline at end of file
@@ -2,139 +2,108 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::env;
 
-fn parse_input(input: &str) -> (Vec<(u32, u32)>, Vec<Vec<u32>>) {
-    let mut page_ordering_rules = Vec::new();
-    let mut updates = Vec::new();
-    let mut is_reading_rules = true;
-
-    for line in input.lines() {
-        if line.is_empty() {
-            is_reading_rules = false;
-            continue;
+use day_05::{
+    build_dependency_graph, export_rules, export_update, find_rule_cycles, format_cycle, parse_input, parse_rules,
+    parse_updates, validate_update, ExportFormat, Input, RuleMatrix,
+};
+
+/// Lit `inputs/input.txt`, ou les fichiers donnés par `--rules <fichier> --updates <fichier>`.
+/// Les options sont retirées de `args`.
+fn load_input(args: &mut Vec<String>) -> Result<Input, String> {
+    let mut take_option = |name: &str| -> Result<Option<String>, String> {
+        let Some(i) = args.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+        if i + 1 >= args.len() {
+            return Err(format!("Missing file after {}", name));
         }
-
-        if is_reading_rules {
-            let parts: Vec<u32> = line.split('|')
-                .map(|x| x.parse::<u32>().unwrap())
-                .collect();
-            page_ordering_rules.push((parts[0], parts[1]));
-        } else {
-            let update: Vec<u32> = line.split(',')
-                .map(|x| x.parse::<u32>().unwrap())
-                .collect();
-            updates.push(update);
+        let value = args.remove(i + 1);
+        args.remove(i);
+        Ok(Some(value))
+    };
+    let rules_file = take_option("--rules")?;
+    let updates_file = take_option("--updates")?;
+
+    let read = |path: &str| fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e));
+    match (rules_file, updates_file) {
+        (None, None) => {
+            let current_dir = env::current_dir().expect("Failed to get current directory");
+            let input_file_path = current_dir.join("inputs/input.txt");
+            let input = fs::read_to_string(input_file_path).map_err(|e| format!("Failed to read input file: {}", e))?;
+            parse_input(&input).map_err(|e| format!("input.txt, {}", e))
         }
-    }
-
-    (page_ordering_rules, updates)
-}
-
-fn build_dependency_graph(rules: &[(u32, u32)]) -> HashMap<u32, HashSet<u32>> {
-    let mut graph = HashMap::new();
-    for &(x, y) in rules {
-        graph.entry(x).or_insert_with(HashSet::new).insert(y);
-    }
-    graph
-}
-
-fn is_valid_update(update: &[u32], graph: &HashMap<u32, HashSet<u32>>) -> bool {
-    let positions: HashMap<u32, usize> = update.iter()
-        .enumerate()
-        .map(|(i, &value)| (value, i))
-        .collect();
-
-    for (&x, dependencies) in graph {
-        if let Some(&x_pos) = positions.get(&x) {
-            for &y in dependencies {
-                if let Some(&y_pos) = positions.get(&y) {
-                    if x_pos >= y_pos {
-                        return false; // La règle x|y est violée
-                    }
-                }
-            }
+        (Some(rules_file), Some(updates_file)) => {
+            let rules = parse_rules(&read(&rules_file)?).map_err(|e| format!("{}, {}", rules_file, e))?;
+            let updates = parse_updates(&read(&updates_file)?).map_err(|e| format!("{}, {}", updates_file, e))?;
+            Ok((rules, updates))
         }
+        _ => Err("--rules and --updates must be given together".to_string()),
     }
-
-    true
 }
 
-fn correct_update_order(update: &mut [u32], graph: &HashMap<u32, HashSet<u32>>) {
-    update.sort_by(|&a, &b| {
-        if let Some(dependencies) = graph.get(&a) {
-            // Si a dépend de b, alors a doit être après b
-            if dependencies.contains(&b) {
-                return std::cmp::Ordering::Less;
-            }
-        }
-        if let Some(dependencies) = graph.get(&b) {
-            // Si b dépend de a, alors a doit être avant b
-            if dependencies.contains(&a) {
-                return std::cmp::Ordering::Greater;
-            }
+/// Commande `export <dot|mermaid> [index de la mise à jour]`
+fn export(args: &[String], graph: &HashMap<u32, HashSet<u32>>, updates: &[Vec<u32>]) -> Result<String, String> {
+    let format: ExportFormat = args.first()
+        .ok_or("Usage: export <dot|mermaid> [update index]")?
+        .parse()?;
+    match args.get(1) {
+        None => Ok(export_rules(graph, format)),
+        Some(index) => {
+            let index: usize = index.parse().map_err(|_| format!("Invalid update index: {}", index))?;
+            let update = updates.get(index)
+                .ok_or(format!("Update index {} out of range ({} updates)", index, updates.len()))?;
+            Ok(export_update(update, graph, format))
         }
-        // Sinon, l'ordre n'a pas d'importance
-        std::cmp::Ordering::Equal
-    });
+    }
 }
 
 fn main() {
-    // Lire le fichier d'entrée
-    let current_dir = env::current_dir().expect("Failed to get current directory");
-    let input_file_path = current_dir.join("inputs/input.txt");
-    let input = fs::read_to_string(input_file_path).expect("Failed to read input file");
-
-    // Parser les données d'entrée
-    let (page_ordering_rules, updates) = parse_input(&input);
+    // Lire et parser les données d'entrée
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let (page_ordering_rules, updates) = match load_input(&mut args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
 
     // Construire le graphe des dépendances
     let dependency_graph = build_dependency_graph(&page_ordering_rules);
+    let rule_matrix = RuleMatrix::new(&dependency_graph);
+
+    // Export du graphe des règles au format DOT ou Mermaid
+    if args.first().map(String::as_str) == Some("export") {
+        match export(&args[1..], &dependency_graph, &updates) {
+            Ok(output) => print!("{}", output),
+            Err(e) => eprintln!("{}", e),
+        }
+        return;
+    }
+
+    // Signaler les règles contradictoires (elles ne gênent que si une mise à jour les contient)
+    for cycle in find_rule_cycles(&dependency_graph) {
+        println!("Warning: page ordering rules contain a cycle: {}", format_cycle(&cycle));
+    }
 
     // Vérifier chaque mise à jour
     let mut total_middle_sum_1 = 0;
     let mut total_middle_sum_2 = 0;
-    for mut update in updates {
-        if is_valid_update(&update, &dependency_graph) {
+    for update in updates {
+        if rule_matrix.is_valid_update(&update) {
             // Trouver la page centrale
             let middle = update[update.len() / 2];
             total_middle_sum_1 += middle;
         } else {
-            print!("Invalid update: {:?} ", update);
-            // Corriger l'ordre de la mise à jour
-            correct_update_order(&mut update, &dependency_graph);
-            let middle = update[update.len() / 2];
-            total_middle_sum_2 += middle;
-            println!("Corrected update: {:?}", update);
+            // Expliquer les règles violées et corriger l'ordre de la mise à jour
+            let report = validate_update(&update, &dependency_graph);
+            print!("{}", report);
+            if let Ok(corrected) = &report.corrected {
+                total_middle_sum_2 += corrected[corrected.len() / 2];
+            }
         }
     }
 
     println!("Total sum of middle page numbers: {}", total_middle_sum_1);
     println!("Total sum of middle page numbers after correction: {}", total_middle_sum_2);
 }
-
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_correct_update_order() {
-        let rules = vec![
-            (47, 53), (97, 13), (97, 61), (97, 47), (75, 29), (61, 13), (75, 53), (29, 13),
-            (97, 29), (53, 29), (61, 53), (97, 53), (61, 29), (47, 13), (75, 47), (97, 75),
-            (47, 61), (75, 61), (47, 29), (75, 13), (53, 13)
-        ];
-        let dependency_graph = build_dependency_graph(&rules);
-
-        let mut update1 = vec![75, 97, 47, 61, 53];
-        correct_update_order(&mut update1, &dependency_graph);
-        assert_eq!(update1, vec![97, 75,  47, 61, 53]);
-
-        let mut update2 = vec![97, 13, 75, 29, 47];
-        correct_update_order(&mut update2, &dependency_graph);
-        assert_eq!(update2, vec![97, 75, 47, 29, 13]);
-
-        let mut update3 = vec![61, 13, 29];
-        correct_update_order(&mut update3, &dependency_graph);
-        assert_eq!(update3, vec![61, 29, 13]);
-    }
-}
\ No newline at end of file
@@ -0,0 +1,113 @@
+use std::fmt;
+
+/// Règles `x|y` et mises à jour lues dans l'entrée
+pub type Input = (Vec<(u32, u32)>, Vec<Vec<u32>>);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Numéro de ligne (à partir de 1)
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Lignes utiles d'un fichier : commentaires `#` retirés, espaces et `\r` ignorés,
+/// lignes vides sautées. Chaque ligne est accompagnée de son numéro.
+fn significant_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn parse_page(text: &str, line: usize) -> Result<u32, ParseError> {
+    let text = text.trim();
+    text.parse().map_err(|_| ParseError { line, message: format!("invalid page number {:?}", text) })
+}
+
+fn parse_rule(text: &str, line: usize) -> Result<(u32, u32), ParseError> {
+    match text.split('|').collect::<Vec<_>>()[..] {
+        [x, y] => Ok((parse_page(x, line)?, parse_page(y, line)?)),
+        _ => Err(ParseError { line, message: format!("expected a rule x|y, found {:?}", text) }),
+    }
+}
+
+fn parse_update(text: &str, line: usize) -> Result<Vec<u32>, ParseError> {
+    text.split(',').map(|page| parse_page(page, line)).collect()
+}
+
+/// Lit un fichier ne contenant que des règles `x|y`
+pub fn parse_rules(input: &str) -> Result<Vec<(u32, u32)>, ParseError> {
+    significant_lines(input).map(|(line, text)| parse_rule(text, line)).collect()
+}
+
+/// Lit un fichier ne contenant que des mises à jour `a,b,c`
+pub fn parse_updates(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+    significant_lines(input).map(|(line, text)| parse_update(text, line)).collect()
+}
+
+/// Lit un fichier contenant les règles puis les mises à jour.
+/// Les lignes sont reconnues à leur contenu (`|` pour une règle), donc le nombre de lignes
+/// vides entre les deux sections n'a pas d'importance ; une règle après une mise à jour est refusée.
+pub fn parse_input(input: &str) -> Result<Input, ParseError> {
+    let mut page_ordering_rules = Vec::new();
+    let mut updates = Vec::new();
+
+    for (line, text) in significant_lines(input) {
+        if text.contains('|') {
+            if !updates.is_empty() {
+                return Err(ParseError { line, message: "rule found after the updates".to_string() });
+            }
+            page_ordering_rules.push(parse_rule(text, line)?);
+        } else {
+            updates.push(parse_update(text, line)?);
+        }
+    }
+
+    Ok((page_ordering_rules, updates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        let input = "47|53\n97|13\n\n75,47,61\n97,61,53,29,13\n";
+        let (rules, updates) = parse_input(input).unwrap();
+        assert_eq!(rules, vec![(47, 53), (97, 13)]);
+        assert_eq!(updates, vec![vec![75, 47, 61], vec![97, 61, 53, 29, 13]]);
+    }
+
+    #[test]
+    fn test_parse_input_tolerant() {
+        let input = "# règles\r\n47 | 53 \r\n97|13   # commentaire\r\n\r\n\r\n# mises à jour\r\n75, 47 ,61\r\n \r\n";
+        let (rules, updates) = parse_input(input).unwrap();
+        assert_eq!(rules, vec![(47, 53), (97, 13)]);
+        assert_eq!(updates, vec![vec![75, 47, 61]]);
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        assert_eq!(
+            parse_input("47|53\n\n75,x,61").unwrap_err(),
+            ParseError { line: 3, message: "invalid page number \"x\"".to_string() }
+        );
+        assert_eq!(parse_input("47|53|61").unwrap_err().line, 1);
+        assert_eq!(parse_input("75,61\n47|53").unwrap_err().to_string(), "line 2: rule found after the updates");
+        assert_eq!(parse_input("75,,61").unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn test_parse_separate_files() {
+        assert_eq!(parse_rules("47|53\n\n97|13\n").unwrap(), vec![(47, 53), (97, 13)]);
+        assert_eq!(parse_updates("75,47\n\n# fin\n61\n").unwrap(), vec![vec![75, 47], vec![61]]);
+        assert!(parse_rules("75,47").is_err());
+        assert!(parse_updates("47|53").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::rules::{build_dependency_graph, find_violations};
+
+/// Changement de statut d'une mise à jour suivie après modification des règles
+#[derive(Debug, PartialEq, Eq)]
+pub struct StatusChange {
+    pub update_id: usize,
+    pub valid: bool,
+}
+
+/// Ensemble de règles modifiable qui garde à jour la validité des mises à jour suivies.
+/// Pour chaque mise à jour on retient le nombre de règles violées : ajouter ou retirer x|y
+/// ne touche que les mises à jour contenant à la fois x et y.
+pub struct RuleSet {
+    graph: HashMap<u32, HashSet<u32>>,
+    updates: Vec<Vec<u32>>,
+    positions: Vec<HashMap<u32, usize>>,
+    violation_counts: Vec<usize>,
+    /// Page -> identifiants des mises à jour qui la contiennent
+    updates_by_page: HashMap<u32, Vec<usize>>,
+}
+
+impl RuleSet {
+    pub fn new(rules: &[(u32, u32)]) -> Self {
+        RuleSet {
+            graph: build_dependency_graph(rules),
+            updates: Vec::new(),
+            positions: Vec::new(),
+            violation_counts: Vec::new(),
+            updates_by_page: HashMap::new(),
+        }
+    }
+
+    /// Graphe courant, utilisable avec les autres fonctions du module `rules`
+    pub fn graph(&self) -> &HashMap<u32, HashSet<u32>> {
+        &self.graph
+    }
+
+    pub fn has_rule(&self, x: u32, y: u32) -> bool {
+        self.graph.get(&x).is_some_and(|dependencies| dependencies.contains(&y))
+    }
+
+    /// Suit une mise à jour et renvoie son identifiant
+    pub fn track(&mut self, update: Vec<u32>) -> usize {
+        let id = self.updates.len();
+        let positions: HashMap<u32, usize> = update.iter().enumerate().map(|(i, &page)| (page, i)).collect();
+        for &page in positions.keys() {
+            self.updates_by_page.entry(page).or_default().push(id);
+        }
+        self.violation_counts.push(find_violations(&update, &self.graph).len());
+        self.positions.push(positions);
+        self.updates.push(update);
+        id
+    }
+
+    pub fn update(&self, id: usize) -> &[u32] {
+        &self.updates[id]
+    }
+
+    pub fn is_valid(&self, id: usize) -> bool {
+        self.violation_counts[id] == 0
+    }
+
+    /// Identifiants des mises à jour suivies actuellement valides
+    pub fn valid_updates(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.updates.len()).filter(|&id| self.is_valid(id))
+    }
+
+    /// Ajoute la règle x|y et renvoie les mises à jour devenues invalides
+    pub fn add_rule(&mut self, x: u32, y: u32) -> Vec<StatusChange> {
+        if !self.graph.entry(x).or_default().insert(y) {
+            return Vec::new();
+        }
+        self.apply_rule_change(x, y, true)
+    }
+
+    /// Retire la règle x|y et renvoie les mises à jour redevenues valides
+    pub fn remove_rule(&mut self, x: u32, y: u32) -> Vec<StatusChange> {
+        let removed = self.graph.get_mut(&x).is_some_and(|dependencies| dependencies.remove(&y));
+        if !removed {
+            return Vec::new();
+        }
+        if self.graph[&x].is_empty() {
+            self.graph.remove(&x);
+        }
+        self.apply_rule_change(x, y, false)
+    }
+
+    fn apply_rule_change(&mut self, x: u32, y: u32, added: bool) -> Vec<StatusChange> {
+        let (Some(with_x), Some(with_y)) = (self.updates_by_page.get(&x), self.updates_by_page.get(&y)) else {
+            return Vec::new();
+        };
+        // On parcourt la liste la plus courte et on vérifie l'autre page par position
+        let candidates = if with_x.len() <= with_y.len() { with_x } else { with_y };
+
+        let mut changes = Vec::new();
+        for &id in candidates {
+            let positions = &self.positions[id];
+            let (Some(&x_pos), Some(&y_pos)) = (positions.get(&x), positions.get(&y)) else {
+                continue;
+            };
+            if x_pos < y_pos {
+                continue; // La règle est respectée par cette mise à jour
+            }
+            let count = &mut self.violation_counts[id];
+            if added {
+                *count += 1;
+                if *count == 1 {
+                    changes.push(StatusChange { update_id: id, valid: false });
+                }
+            } else {
+                *count -= 1;
+                if *count == 0 {
+                    changes.push(StatusChange { update_id: id, valid: true });
+                }
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::is_valid_update;
+
+    #[test]
+    fn test_rule_set_add_and_remove() {
+        let mut rule_set = RuleSet::new(&[(47, 53), (97, 75)]);
+        let a = rule_set.track(vec![75, 97, 47, 53]);
+        let b = rule_set.track(vec![97, 75, 53, 47]);
+        let c = rule_set.track(vec![13, 29]);
+        assert!(!rule_set.is_valid(a));
+        assert!(!rule_set.is_valid(b));
+        assert!(rule_set.is_valid(c));
+
+        // a ne viole plus aucune règle ; b viole encore 47|53
+        assert_eq!(rule_set.remove_rule(97, 75), vec![StatusChange { update_id: a, valid: true }]);
+        assert_eq!(rule_set.remove_rule(47, 53), vec![StatusChange { update_id: b, valid: true }]);
+        assert_eq!(rule_set.valid_updates().collect::<Vec<_>>(), vec![a, b, c]);
+
+        assert_eq!(rule_set.add_rule(29, 13), vec![StatusChange { update_id: c, valid: false }]);
+        // Règle déjà présente ou absente : aucun changement
+        assert!(rule_set.add_rule(29, 13).is_empty());
+        assert!(rule_set.remove_rule(1, 2).is_empty());
+        // Règle respectée par toutes les mises à jour
+        assert!(rule_set.add_rule(97, 47).is_empty());
+        assert!(rule_set.has_rule(97, 47));
+        assert!(!rule_set.has_rule(97, 75));
+    }
+
+    #[test]
+    fn test_rule_set_matches_full_validation() {
+        let rules = vec![(47, 53), (97, 13), (97, 61), (97, 47), (75, 29), (61, 13), (75, 53), (29, 13)];
+        let updates = vec![
+            vec![75, 47, 61, 53, 29],
+            vec![97, 61, 53, 29, 13],
+            vec![75, 29, 13],
+            vec![75, 97, 47, 61, 53],
+            vec![61, 13, 29],
+            vec![97, 13, 75, 29, 47],
+        ];
+        let mut rule_set = RuleSet::new(&rules);
+        for update in &updates {
+            rule_set.track(update.clone());
+        }
+
+        let edits = [(13, 29, true), (29, 13, false), (53, 47, true), (47, 53, false), (61, 13, false)];
+        for (x, y, add) in edits {
+            if add {
+                rule_set.add_rule(x, y);
+            } else {
+                rule_set.remove_rule(x, y);
+            }
+            for (id, update) in updates.iter().enumerate() {
+                assert_eq!(rule_set.is_valid(id), is_valid_update(update, rule_set.graph()));
+            }
+        }
+    }
+}
@@ -0,0 +1,674 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OrderingError {
+    /// Les règles forment un cycle entre ces pages
+    Cycle(Vec<u32>),
+    /// Plusieurs ordres valides placent une page différente au milieu
+    AmbiguousMiddle(Vec<u32>),
+}
+
+impl fmt::Display for OrderingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderingError::Cycle(pages) => write!(f, "rules contain a cycle: {}", format_cycle(pages)),
+            OrderingError::AmbiguousMiddle(pages) => {
+                write!(f, "middle page is ambiguous, candidates: {:?}", pages)
+            }
+        }
+    }
+}
+
+pub fn build_dependency_graph(rules: &[(u32, u32)]) -> HashMap<u32, HashSet<u32>> {
+    let mut graph = HashMap::new();
+    for &(x, y) in rules {
+        graph.entry(x).or_insert_with(HashSet::new).insert(y);
+    }
+    graph
+}
+
+pub fn is_valid_update(update: &[u32], graph: &HashMap<u32, HashSet<u32>>) -> bool {
+    let positions: HashMap<u32, usize> = update.iter()
+        .enumerate()
+        .map(|(i, &value)| (value, i))
+        .collect();
+
+    for (&x, dependencies) in graph {
+        if let Some(&x_pos) = positions.get(&x) {
+            for &y in dependencies {
+                if let Some(&y_pos) = positions.get(&y) {
+                    if x_pos >= y_pos {
+                        return false; // La règle x|y est violée
+                    }
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// Matrice d'adjacence des règles sous forme de bitset.
+/// Les pages sont renumérotées de 0 à n - 1 ; la ligne de x contient le bit de y si x|y.
+pub struct RuleMatrix {
+    ids: HashMap<u32, usize>,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+impl RuleMatrix {
+    pub fn new(graph: &HashMap<u32, HashSet<u32>>) -> Self {
+        let mut pages: Vec<u32> = graph.iter()
+            .flat_map(|(&x, dependencies)| std::iter::once(x).chain(dependencies.iter().copied()))
+            .collect::<HashSet<u32>>()
+            .into_iter()
+            .collect();
+        pages.sort_unstable();
+        let ids: HashMap<u32, usize> = pages.iter().enumerate().map(|(i, &page)| (page, i)).collect();
+
+        let words_per_row = pages.len().div_ceil(64);
+        let mut bits = vec![0u64; pages.len() * words_per_row];
+        for (x, dependencies) in graph {
+            let row = ids[x] * words_per_row;
+            for y in dependencies {
+                let col = ids[y];
+                bits[row + col / 64] |= 1 << (col % 64);
+            }
+        }
+        RuleMatrix { ids, words_per_row, bits }
+    }
+
+    fn has_rule_ids(&self, x: usize, y: usize) -> bool {
+        self.bits[x * self.words_per_row + y / 64] & (1 << (y % 64)) != 0
+    }
+
+    /// Vrai si la règle x|y existe
+    pub fn has_rule(&self, x: u32, y: u32) -> bool {
+        match (self.ids.get(&x), self.ids.get(&y)) {
+            (Some(&x), Some(&y)) => self.has_rule_ids(x, y),
+            _ => false,
+        }
+    }
+
+    /// Même résultat que `is_valid_update`, mais en O(k²) sur les k pages de la mise à jour
+    /// au lieu de parcourir tout le graphe
+    pub fn is_valid_update(&self, update: &[u32]) -> bool {
+        // Les pages absentes des règles ne peuvent violer aucune règle
+        let ids: Vec<usize> = update.iter().filter_map(|page| self.ids.get(page).copied()).collect();
+        for (i, &before) in ids.iter().enumerate() {
+            for &after in &ids[i + 1..] {
+                if self.has_rule_ids(after, before) {
+                    return false; // La règle after|before est violée
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Sous-graphe des règles restreint aux pages d'une mise à jour, indexé par position
+fn induced_subgraph(update: &[u32], graph: &HashMap<u32, HashSet<u32>>) -> Vec<Vec<usize>> {
+    let positions: HashMap<u32, usize> = update.iter()
+        .enumerate()
+        .map(|(i, &value)| (value, i))
+        .collect();
+
+    update.iter()
+        .map(|page| {
+            let mut successors: Vec<usize> = graph.get(page)
+                .map(|dependencies| dependencies.iter().filter_map(|y| positions.get(y).copied()).collect())
+                .unwrap_or_default();
+            successors.sort_unstable();
+            successors
+        })
+        .collect()
+}
+
+/// Composantes fortement connexes (algorithme de Tarjan)
+struct Tarjan<'a> {
+    successors: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn run(successors: &'a [Vec<usize>]) -> Vec<Vec<usize>> {
+        let n = successors.len();
+        let mut tarjan = Tarjan {
+            successors,
+            index: vec![None; n],
+            low_link: vec![0; n],
+            on_stack: vec![false; n],
+            stack: Vec::new(),
+            next_index: 0,
+            components: Vec::new(),
+        };
+        for v in 0..n {
+            if tarjan.index[v].is_none() {
+                tarjan.visit(v);
+            }
+        }
+        tarjan.components
+    }
+
+    fn visit(&mut self, v: usize) {
+        self.index[v] = Some(self.next_index);
+        self.low_link[v] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(v);
+        self.on_stack[v] = true;
+
+        for &w in &self.successors[v] {
+            match self.index[w] {
+                None => {
+                    self.visit(w);
+                    self.low_link[v] = self.low_link[v].min(self.low_link[w]);
+                }
+                Some(w_index) if self.on_stack[w] => {
+                    self.low_link[v] = self.low_link[v].min(w_index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        // v est la racine d'une composante : on la dépile entièrement
+        if Some(self.low_link[v]) == self.index[v] {
+            let mut component = Vec::new();
+            while let Some(w) = self.stack.pop() {
+                self.on_stack[w] = false;
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            component.sort_unstable();
+            self.components.push(component);
+        }
+    }
+}
+
+/// Un cycle concret par composante fortement connexe non triviale.
+/// Chaque cycle part du plus petit sommet de sa composante (sans le répéter à la fin).
+fn cycles_in(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut cycles = Vec::new();
+    for component in Tarjan::run(successors) {
+        let start = component[0];
+        if component.len() == 1 && !successors[start].contains(&start) {
+            continue;
+        }
+
+        // Parcours en largeur dans la composante jusqu'à revenir au départ
+        let in_component: HashSet<usize> = component.iter().copied().collect();
+        let mut parent: HashMap<usize, usize> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        let mut last = None;
+        'search: while let Some(x) = queue.pop_front() {
+            for &y in &successors[x] {
+                if y == start {
+                    last = Some(x);
+                    break 'search;
+                }
+                if in_component.contains(&y) && !parent.contains_key(&y) {
+                    parent.insert(y, x);
+                    queue.push_back(y);
+                }
+            }
+        }
+
+        let mut cycle = Vec::new();
+        let mut current = last.expect("a strongly connected component always closes a cycle");
+        while current != start {
+            cycle.push(current);
+            current = parent[&current];
+        }
+        cycle.push(start);
+        cycle.reverse();
+        cycles.push(cycle);
+    }
+    cycles.sort();
+    cycles
+}
+
+/// Cycles des règles restreintes à un ensemble de pages
+pub fn find_cycles(pages: &[u32], graph: &HashMap<u32, HashSet<u32>>) -> Vec<Vec<u32>> {
+    cycles_in(&induced_subgraph(pages, graph))
+        .into_iter()
+        .map(|cycle| cycle.iter().map(|&i| pages[i]).collect())
+        .collect()
+}
+
+/// Cycles du graphe global des règles
+pub fn find_rule_cycles(graph: &HashMap<u32, HashSet<u32>>) -> Vec<Vec<u32>> {
+    let mut pages: Vec<u32> = graph.iter()
+        .flat_map(|(&x, dependencies)| std::iter::once(x).chain(dependencies.iter().copied()))
+        .collect::<HashSet<u32>>()
+        .into_iter()
+        .collect();
+    pages.sort_unstable();
+    find_cycles(&pages, graph)
+}
+
+/// Affiche un cycle sous la forme `47 → 53 → 29 → 47`
+pub fn format_cycle(cycle: &[u32]) -> String {
+    cycle.iter()
+        .chain(cycle.first())
+        .map(|page| page.to_string())
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+/// Tri topologique (Kahn) ; à égalité, la page la plus tôt dans la mise à jour passe d'abord.
+/// Renvoie None si le graphe contient un cycle.
+fn topological_order(successors: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let mut in_degree = vec![0; successors.len()];
+    for targets in successors {
+        for &y in targets {
+            in_degree[y] += 1;
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..successors.len())
+        .filter(|&i| in_degree[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(successors.len());
+    while let Some(Reverse(x)) = ready.pop() {
+        order.push(x);
+        for &y in &successors[x] {
+            in_degree[y] -= 1;
+            if in_degree[y] == 0 {
+                ready.push(Reverse(y));
+            }
+        }
+    }
+
+    (order.len() == successors.len()).then_some(order)
+}
+
+/// Fermeture transitive : `reach[x][y]` vaut vrai si un chemin mène de x à y
+fn reachability(successors: &[Vec<usize>]) -> Vec<Vec<bool>> {
+    let n = successors.len();
+    (0..n)
+        .map(|start| {
+            let mut seen = vec![false; n];
+            let mut stack = vec![start];
+            while let Some(x) = stack.pop() {
+                for &y in &successors[x] {
+                    if !seen[y] {
+                        seen[y] = true;
+                        stack.push(y);
+                    }
+                }
+            }
+            seen
+        })
+        .collect()
+}
+
+/// Sommets pouvant occuper la position centrale dans au moins un ordre topologique.
+/// Un sommet peut occuper les positions de |ancêtres| à n - 1 - |descendants|.
+fn middle_candidates(successors: &[Vec<usize>]) -> Vec<usize> {
+    let n = successors.len();
+    let middle = n / 2;
+    let mut ancestors = vec![0; n];
+    let mut descendants = vec![0; n];
+
+    for (x, reached) in reachability(successors).iter().enumerate() {
+        for (y, _) in reached.iter().enumerate().filter(|(_, &reached)| reached) {
+            descendants[x] += 1;
+            ancestors[y] += 1;
+        }
+    }
+
+    (0..n)
+        .filter(|&v| ancestors[v] <= middle && middle + descendants[v] < n)
+        .collect()
+}
+
+pub fn correct_update_order(update: &mut [u32], graph: &HashMap<u32, HashSet<u32>>) -> Result<(), OrderingError> {
+    if update.is_empty() {
+        return Ok(());
+    }
+
+    let successors = induced_subgraph(update, graph);
+    let Some(order) = topological_order(&successors) else {
+        let cycle = cycles_in(&successors).swap_remove(0);
+        return Err(OrderingError::Cycle(cycle.iter().map(|&i| update[i]).collect()));
+    };
+
+    // Si plusieurs pages peuvent se retrouver au milieu, la réponse dépend du départage
+    let candidates = middle_candidates(&successors);
+    if candidates.len() > 1 {
+        return Err(OrderingError::AmbiguousMiddle(candidates.iter().map(|&i| update[i]).collect()));
+    }
+
+    let corrected: Vec<u32> = order.iter().map(|&i| update[i]).collect();
+    update.copy_from_slice(&corrected);
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Règle x|y violée
+    pub rule: (u32, u32),
+    /// Positions de x et de y dans la mise à jour
+    pub positions: (usize, usize),
+}
+
+#[derive(Debug)]
+pub struct ValidationReport {
+    pub update: Vec<u32>,
+    pub violations: Vec<Violation>,
+    /// Nombre minimal de pages à déplacer ; None si les règles forment un cycle
+    pub minimal_moves: Option<usize>,
+    pub corrected: Result<Vec<u32>, OrderingError>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            return writeln!(f, "Valid update: {:?}", self.update);
+        }
+        writeln!(f, "Invalid update: {:?}", self.update)?;
+        for violation in &self.violations {
+            let (x, y) = violation.rule;
+            let (x_pos, y_pos) = violation.positions;
+            writeln!(f, "  rule {}|{} violated: {} at position {}, {} at position {}", x, y, x, x_pos, y, y_pos)?;
+        }
+        if let Some(moves) = self.minimal_moves {
+            writeln!(f, "  minimal moves: {}", moves)?;
+        }
+        match &self.corrected {
+            Ok(corrected) => {
+                writeln!(f, "  corrected update: {:?}", corrected)?;
+                for line in order_diff(&self.update, corrected) {
+                    writeln!(f, "    {}", line)?;
+                }
+                Ok(())
+            }
+            Err(e) => writeln!(f, "  cannot correct update: {}", e),
+        }
+    }
+}
+
+/// Toutes les règles violées par une mise à jour, triées par position
+pub fn find_violations(update: &[u32], graph: &HashMap<u32, HashSet<u32>>) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for (x_pos, y_positions) in induced_subgraph(update, graph).iter().enumerate() {
+        for &y_pos in y_positions {
+            if x_pos >= y_pos {
+                violations.push(Violation { rule: (update[x_pos], update[y_pos]), positions: (x_pos, y_pos) });
+            }
+        }
+    }
+    violations.sort_by_key(|violation| (violation.positions.1, violation.positions.0));
+    violations
+}
+
+/// Nombre minimal de pages à déplacer pour obtenir un ordre valide.
+/// Les pages conservées ne doivent contenir aucune paire inversée (au sens de la fermeture
+/// transitive) : c'est une antichaîne de l'ordre des inversions, dont la taille maximale vaut
+/// n - couplage maximal (Dilworth). Il faut donc déplacer autant de pages que le couplage.
+fn minimal_moves(successors: &[Vec<usize>]) -> Option<usize> {
+    topological_order(successors)?;
+    let reach = reachability(successors);
+    let n = successors.len();
+
+    // Chemin augmentant (Kuhn) : i est apparié à un j > i qui doit pourtant le précéder
+    fn augment(i: usize, reach: &[Vec<bool>], seen: &mut [bool], matched: &mut [Option<usize>]) -> bool {
+        for j in i + 1..reach.len() {
+            if reach[j][i] && !seen[j] {
+                seen[j] = true;
+                if matched[j].is_none_or(|k| augment(k, reach, seen, matched)) {
+                    matched[j] = Some(i);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    let mut matched = vec![None; n];
+    Some((0..n).filter(|&i| augment(i, &reach, &mut vec![false; n], &mut matched)).count())
+}
+
+/// Diff ligne à ligne entre l'ordre d'origine et l'ordre corrigé
+/// (pages conservées préfixées par ' ', retirées par '-', insérées par '+')
+fn order_diff(original: &[u32], corrected: &[u32]) -> Vec<String> {
+    let (n, m) = (original.len(), corrected.len());
+    // Plus longue sous-séquence commune
+    let mut lcs = vec![vec![0; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if original[i] == corrected[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && original[i] == corrected[j] {
+            lines.push(format!("  {}", original[i]));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(format!("- {}", original[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", corrected[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+pub fn validate_update(update: &[u32], graph: &HashMap<u32, HashSet<u32>>) -> ValidationReport {
+    let mut corrected = update.to_vec();
+    ValidationReport {
+        update: update.to_vec(),
+        violations: find_violations(update, graph),
+        minimal_moves: minimal_moves(&induced_subgraph(update, graph)),
+        corrected: correct_update_order(&mut corrected, graph).map(|()| corrected),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correct_update_order() {
+        let rules = vec![
+            (47, 53), (97, 13), (97, 61), (97, 47), (75, 29), (61, 13), (75, 53), (29, 13),
+            (97, 29), (53, 29), (61, 53), (97, 53), (61, 29), (47, 13), (75, 47), (97, 75),
+            (47, 61), (75, 61), (47, 29), (75, 13), (53, 13)
+        ];
+        let dependency_graph = build_dependency_graph(&rules);
+
+        let mut update1 = vec![75, 97, 47, 61, 53];
+        correct_update_order(&mut update1, &dependency_graph).unwrap();
+        assert_eq!(update1, vec![97, 75,  47, 61, 53]);
+
+        let mut update2 = vec![97, 13, 75, 29, 47];
+        correct_update_order(&mut update2, &dependency_graph).unwrap();
+        assert_eq!(update2, vec![97, 75, 47, 29, 13]);
+
+        let mut update3 = vec![61, 13, 29];
+        correct_update_order(&mut update3, &dependency_graph).unwrap();
+        assert_eq!(update3, vec![61, 29, 13]);
+    }
+
+    #[test]
+    fn test_correct_update_order_partial_rules() {
+        // 3, 4 et 5 ne sont pas comparables entre eux
+        let rules = vec![(1, 2), (2, 3), (2, 4)];
+        let dependency_graph = build_dependency_graph(&rules);
+
+        let mut update = vec![4, 3, 2, 1, 5];
+        assert_eq!(
+            correct_update_order(&mut update, &dependency_graph),
+            Err(OrderingError::AmbiguousMiddle(vec![4, 3, 2, 5]))
+        );
+
+        let mut update = vec![4, 2, 1];
+        correct_update_order(&mut update, &dependency_graph).unwrap();
+        assert_eq!(update, vec![1, 2, 4]);
+
+        let mut update = vec![4, 3, 2];
+        correct_update_order(&mut update, &dependency_graph).unwrap_err();
+
+        // Départage déterministe : les pages libres gardent leur ordre d'origine
+        let mut update = vec![3, 4, 1, 2, 9];
+        let rules = vec![(1, 2), (2, 3), (2, 4), (1, 9), (9, 2)];
+        correct_update_order(&mut update, &build_dependency_graph(&rules)).unwrap();
+        assert_eq!(update, vec![1, 9, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_correct_update_order_ambiguous_middle() {
+        let rules = vec![(1, 2)];
+        let dependency_graph = build_dependency_graph(&rules);
+
+        let mut update = vec![2, 7, 1];
+        assert_eq!(
+            correct_update_order(&mut update, &dependency_graph),
+            Err(OrderingError::AmbiguousMiddle(vec![2, 7, 1]))
+        );
+        // La mise à jour n'est pas modifiée en cas d'erreur
+        assert_eq!(update, vec![2, 7, 1]);
+    }
+
+    #[test]
+    fn test_correct_update_order_cycle() {
+        let rules = vec![(1, 2), (2, 3), (3, 1)];
+        let dependency_graph = build_dependency_graph(&rules);
+
+        let mut update = vec![1, 2, 3];
+        assert_eq!(
+            correct_update_order(&mut update, &dependency_graph),
+            Err(OrderingError::Cycle(vec![1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let rules = vec![(47, 53), (53, 29), (29, 47), (29, 13), (61, 13), (13, 61), (75, 75)];
+        let dependency_graph = build_dependency_graph(&rules);
+
+        let cycles = find_rule_cycles(&dependency_graph);
+        assert_eq!(cycles, vec![vec![13, 61], vec![29, 47, 53], vec![75]]);
+        assert_eq!(format_cycle(&cycles[1]), "29 → 47 → 53 → 29");
+
+        // Dans une mise à jour, seul le sous-graphe induit compte
+        assert_eq!(find_cycles(&[47, 53, 29], &dependency_graph), vec![vec![47, 53, 29]]);
+        assert!(find_cycles(&[47, 53, 13], &dependency_graph).is_empty());
+
+        let mut update = vec![53, 29, 47];
+        let error = correct_update_order(&mut update, &dependency_graph).unwrap_err();
+        assert_eq!(error.to_string(), "rules contain a cycle: 53 → 29 → 47 → 53");
+    }
+
+    #[test]
+    fn test_find_rule_cycles_acyclic() {
+        let rules = vec![(47, 53), (97, 13), (97, 61), (97, 47), (75, 29), (61, 13)];
+        assert!(find_rule_cycles(&build_dependency_graph(&rules)).is_empty());
+    }
+
+    #[test]
+    fn test_validate_update() {
+        let rules = vec![
+            (47, 53), (97, 13), (97, 61), (97, 47), (75, 29), (61, 13), (75, 53), (29, 13),
+            (97, 29), (53, 29), (61, 53), (97, 53), (61, 29), (47, 13), (75, 47), (97, 75),
+            (47, 61), (75, 61), (47, 29), (75, 13), (53, 13)
+        ];
+        let dependency_graph = build_dependency_graph(&rules);
+
+        let report = validate_update(&[75, 47, 61, 53, 29], &dependency_graph);
+        assert!(report.is_valid());
+        assert_eq!(report.minimal_moves, Some(0));
+
+        let report = validate_update(&[75, 97, 47, 61, 53], &dependency_graph);
+        assert!(!report.is_valid());
+        assert_eq!(report.violations, vec![Violation { rule: (97, 75), positions: (1, 0) }]);
+        assert_eq!(report.minimal_moves, Some(1));
+        assert_eq!(report.corrected, Ok(vec![97, 75, 47, 61, 53]));
+
+        let report = validate_update(&[97, 13, 75, 29, 47], &dependency_graph);
+        assert_eq!(report.violations, vec![
+            Violation { rule: (75, 13), positions: (2, 1) },
+            Violation { rule: (29, 13), positions: (3, 1) },
+            Violation { rule: (47, 13), positions: (4, 1) },
+            Violation { rule: (47, 29), positions: (4, 3) },
+        ]);
+        assert_eq!(report.minimal_moves, Some(2));
+    }
+
+    #[test]
+    fn test_minimal_moves_uses_transitive_rules() {
+        // Une seule règle directe est violée (2|3), mais 3 et 4 doivent aussi suivre 1 et 2
+        let rules = vec![(1, 2), (2, 3), (3, 4)];
+        let dependency_graph = build_dependency_graph(&rules);
+        let report = validate_update(&[3, 4, 1, 2], &dependency_graph);
+        assert_eq!(report.violations, vec![Violation { rule: (2, 3), positions: (3, 0) }]);
+        assert_eq!(report.minimal_moves, Some(2));
+        assert_eq!(report.corrected, Ok(vec![1, 2, 3, 4]));
+
+        let rules = vec![(1, 2), (2, 3), (3, 1)];
+        let report = validate_update(&[2, 1, 3], &build_dependency_graph(&rules));
+        assert_eq!(report.minimal_moves, None);
+    }
+
+    #[test]
+    fn test_order_diff() {
+        assert_eq!(
+            order_diff(&[75, 97, 47, 61, 53], &[97, 75, 47, 61, 53]),
+            vec!["- 75", "  97", "+ 75", "  47", "  61", "  53"]
+        );
+        assert_eq!(order_diff(&[1, 2], &[1, 2]), vec!["  1", "  2"]);
+    }
+
+    #[test]
+    fn test_rule_matrix() {
+        let rules = vec![
+            (47, 53), (97, 13), (97, 61), (97, 47), (75, 29), (61, 13), (75, 53), (29, 13),
+            (97, 29), (53, 29), (61, 53), (97, 53), (61, 29), (47, 13), (75, 47), (97, 75),
+            (47, 61), (75, 61), (47, 29), (75, 13), (53, 13)
+        ];
+        let dependency_graph = build_dependency_graph(&rules);
+        let rule_matrix = RuleMatrix::new(&dependency_graph);
+
+        assert!(rule_matrix.has_rule(97, 75));
+        assert!(!rule_matrix.has_rule(75, 97));
+        assert!(!rule_matrix.has_rule(1, 97));
+
+        let updates = vec![
+            vec![75, 47, 61, 53, 29],
+            vec![97, 61, 53, 29, 13],
+            vec![75, 29, 13],
+            vec![75, 97, 47, 61, 53],
+            vec![61, 13, 29],
+            vec![97, 13, 75, 29, 47],
+            vec![5, 97, 8, 75],
+            vec![],
+        ];
+        for update in &updates {
+            assert_eq!(rule_matrix.is_valid_update(update), is_valid_update(update, &dependency_graph));
+        }
+    }
+}
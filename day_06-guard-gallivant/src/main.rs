@@ -1,8 +1,18 @@
 use std::fs;
 use std::env;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+#[cfg(feature = "progress")]
 use indicatif::ProgressBar;
+use rayon::prelude::*;
 
+mod unbounded;
+
+use unbounded::{patrol_unbounded, Outcome, SparseMap, TiledMap, UnboundedPatrol, World};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
     Up,
     Down,
@@ -10,14 +20,143 @@ enum Direction {
     Right,
 }
 
+impl Direction {
+    const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    fn index(self) -> usize {
+        match self {
+            Direction::Up => 0,
+            Direction::Down => 1,
+            Direction::Left => 2,
+            Direction::Right => 3,
+        }
+    }
+
+    fn delta(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+            Direction::Right => Direction::Down,
+        }
+    }
+
+    fn turn_left(self) -> Direction {
+        self.turn_right().reverse()
+    }
+
+    fn reverse(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    fn from_glyph(glyph: char) -> Option<Direction> {
+        match glyph {
+            '^' => Some(Direction::Up),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            '>' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+
+    fn glyph(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+}
+
+/// Position et direction du garde
+type State = ((i32, i32), Direction);
+
+/// Ce que fait le garde face à un obstacle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TurnPolicy {
+    Right,
+    Left,
+    Reverse,
+    /// Tourne à droite, puis à gauche, puis à droite... La direction suffit donc à savoir
+    /// quel sera le prochain virage : le garde n'alterne qu'entre deux directions.
+    Alternate,
+}
+
+impl TurnPolicy {
+    /// Nouvelle direction au virage numéro `turns` (à partir de 0)
+    fn turn(self, direction: Direction, turns: usize) -> Direction {
+        match self {
+            TurnPolicy::Right => direction.turn_right(),
+            TurnPolicy::Left => direction.turn_left(),
+            TurnPolicy::Reverse => direction.reverse(),
+            TurnPolicy::Alternate if turns.is_multiple_of(2) => direction.turn_right(),
+            TurnPolicy::Alternate => direction.turn_left(),
+        }
+    }
+}
+
+impl FromStr for TurnPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "right" => Ok(TurnPolicy::Right),
+            "left" => Ok(TurnPolicy::Left),
+            "reverse" => Ok(TurnPolicy::Reverse),
+            "alternate" => Ok(TurnPolicy::Alternate),
+            _ => Err(format!("Unknown turn policy: {} (expected right, left, reverse or alternate)", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Guard {
     position: (i32, i32),
     direction: Direction,
+    policy: TurnPolicy,
+    /// Nombre de virages déjà effectués
+    turns: usize,
 }
 
 impl Guard {
     fn new(position: (i32, i32), direction: Direction) -> Guard {
-        Guard { position, direction }
+        Guard { position, direction, policy: TurnPolicy::Right, turns: 0 }
+    }
+
+    fn with_policy(self, policy: TurnPolicy) -> Guard {
+        Guard { policy, ..self }
+    }
+
+    fn state(&self) -> State {
+        (self.position, self.direction)
     }
 
     fn move_forward(&mut self) {
@@ -38,95 +177,356 @@ impl Guard {
         }
     }
 
-    fn turn_right(&mut self) {
-        self.direction = match self.direction {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
+    fn turn(&mut self) {
+        self.direction = self.policy.turn(self.direction, self.turns);
+        self.turns += 1;
     }
 }
 
-#[derive(Clone)]
+/// Ensemble d'entiers de taille fixe, un bit par élément
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> BitSet {
+        BitSet { words: vec![0; len.div_ceil(64)] }
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// Renvoie vrai si l'élément n'était pas déjà présent
+    fn insert(&mut self, i: usize) -> bool {
+        let word = &mut self.words[i / 64];
+        let bit = 1 << (i % 64);
+        let inserted = *word & bit == 0;
+        *word |= bit;
+        inserted
+    }
+}
+
+/// Plateau sous forme compacte : une ligne de bits par rangée pour les obstacles.
+/// Les gardes ne sont pas stockés dans le plateau, seulement leurs positions de départ.
 struct Grid {
-    grid: Vec<Vec<char>>,
+    width: usize,
+    height: usize,
+    /// Nombre de mots de 64 bits par rangée
+    stride: usize,
+    obstacles: Vec<u64>,
+    starts: Vec<State>,
 }
 
 impl Grid {
     fn new(input: &str) -> Grid {
-        let grid = input
-            .lines()
-            .map(|line| line.chars().collect())
-            .collect();
-        Grid { grid }
+        let lines: Vec<&str> = input.lines().collect();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let height = lines.len();
+        let stride = width.div_ceil(64);
+        let mut obstacles = vec![0; stride * height];
+        let mut starts = Vec::new();
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                if ch == '#' {
+                    obstacles[y * stride + x / 64] |= 1 << (x % 64);
+                } else if let Some(direction) = Direction::from_glyph(ch) {
+                    starts.push(((x as i32, y as i32), direction));
+                }
+            }
+        }
+        Grid { width, height, stride, obstacles, starts }
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn is_obstacle(&self, x: i32, y: i32) -> bool {
+        self.contains(x, y) && self.obstacles[y as usize * self.stride + x as usize / 64] & (1 << (x % 64)) != 0
+    }
+
+    /// Numéro de la case dans un parcours ligne par ligne
+    fn cell_index(&self, position: (i32, i32)) -> usize {
+        position.1 as usize * self.width + position.0 as usize
+    }
+}
+
+/// États déjà parcourus : un ensemble de cases par direction
+struct Visited {
+    directions: [BitSet; 4],
+}
+
+impl Visited {
+    fn new(grid: &Grid) -> Visited {
+        let cells = grid.width() * grid.height();
+        Visited { directions: std::array::from_fn(|_| BitSet::new(cells)) }
+    }
+
+    /// Renvoie faux si le garde est déjà passé par cette case dans cette direction
+    fn insert(&mut self, grid: &Grid, (position, direction): State) -> bool {
+        self.directions[direction.index()].insert(grid.cell_index(position))
+    }
+
+    fn contains_cell(&self, grid: &Grid, position: (i32, i32)) -> bool {
+        let i = grid.cell_index(position);
+        self.directions.iter().any(|cells| cells.contains(i))
+    }
+
+    /// Nombre de cases visitées, quelle que soit la direction
+    fn count_cells(&self) -> usize {
+        let [up, down, left, right] = &self.directions;
+        (0..up.words.len())
+            .map(|i| (up.words[i] | down.words[i] | left.words[i] | right.words[i]).count_ones() as usize)
+            .sum()
+    }
+}
+
+/// Pour chaque case et chaque direction, la case où le garde s'arrête devant le prochain
+/// obstacle, ou None s'il sort du plateau
+struct JumpTable {
+    width: usize,
+    stops: [Vec<Option<(i32, i32)>>; 4],
+}
+
+impl JumpTable {
+    fn new(grid: &Grid) -> JumpTable {
+        let (width, height) = (grid.width(), grid.height());
+        let mut stops: [Vec<Option<(i32, i32)>>; 4] = Default::default();
+        for direction in Direction::ALL {
+            let (dx, dy) = direction.delta();
+            let table = &mut stops[direction.index()];
+            *table = vec![None; width * height];
+            // On parcourt les cases en partant du bord vers lequel on se dirige,
+            // pour que la case suivante soit toujours déjà calculée
+            let xs: Vec<i32> = if dx > 0 { (0..width as i32).rev().collect() } else { (0..width as i32).collect() };
+            let ys: Vec<i32> = if dy > 0 { (0..height as i32).rev().collect() } else { (0..height as i32).collect() };
+            for &y in &ys {
+                for &x in &xs {
+                    let (nx, ny) = (x + dx, y + dy);
+                    table[y as usize * width + x as usize] = if grid.is_obstacle(nx, ny) {
+                        Some((x, y))
+                    } else if !grid.contains(nx, ny) {
+                        None
+                    } else {
+                        table[ny as usize * width + nx as usize]
+                    };
+                }
+            }
+        }
+        JumpTable { width, stops }
+    }
+
+    fn stop(&self, position: (i32, i32), direction: Direction) -> Option<(i32, i32)> {
+        self.stops[direction.index()][position.1 as usize * self.width + position.0 as usize]
     }
+}
+
+/// Vrai si le garde boucle lorsqu'un obstacle est ajouté en `obstruction`.
+/// Le garde saute d'obstacle en obstacle grâce à la table ; seul l'obstacle ajouté est
+/// vérifié à la main.
+fn loops_with_obstruction(jumps: &JumpTable, mut guard: Guard, obstruction: (i32, i32)) -> bool {
+    let mut states = HashSet::new();
+    loop {
+        if !states.insert(guard.state()) {
+            return true;
+        }
+        let (position, direction) = guard.state();
+        let stop = jumps.stop(position, direction);
 
-    fn get(&self, x: i32, y: i32) -> Option<char> {
-        if y >= 0 && (y as usize) < self.grid.len() {
-            let row = &self.grid[y as usize];
-            if x >= 0 && (x as usize) < row.len() {
-                return Some(row[x as usize]);
+        // L'obstacle ajouté est-il sur le trajet, avant l'arrêt prévu ?
+        let (dx, dy) = direction.delta();
+        let (ox, oy) = (obstruction.0 - position.0, obstruction.1 - position.1);
+        let distance = if dx == 0 { oy * dy } else { ox * dx };
+        let aligned = if dx == 0 { ox == 0 } else { oy == 0 };
+        let stop_distance = stop.map(|(sx, sy)| (sx - position.0) * dx + (sy - position.1) * dy);
+        if aligned && distance > 0 && stop_distance.is_none_or(|d| distance <= d) {
+            guard.position = (obstruction.0 - dx, obstruction.1 - dy);
+        } else {
+            match stop {
+                Some(stop) => guard.position = stop,
+                None => return false,
             }
         }
-        None
+        guard.turn();
     }
 }
 
-struct Game {
-    grid: Grid,
+/// Trajet de la partie 1 : les cases dans l'ordre de première visite, chacune avec l'état
+/// du garde juste avant d'y entrer. La case de départ n'en fait pas partie : aucun état
+/// ne la précède.
+struct Patrol {
+    first_visits: Vec<((i32, i32), Guard)>,
+    /// Le trajet d'origine boucle déjà
+    loops: bool,
+}
+
+/// Tout ce qui change pendant une simulation. Le plateau, lui, n'est jamais modifié :
+/// il est partagé par référence entre toutes les simulations.
+struct PatrolState {
     guard: Guard,
-    visited: HashSet<(i32, i32)>,
+    /// États déjà rencontrés : en revoir un signifie que le garde boucle
+    visited: Visited,
+    /// Obstacle ajouté pour cette simulation seulement
+    obstruction: Option<(i32, i32)>,
+}
+
+impl PatrolState {
+    fn new(grid: &Grid, guard: Guard) -> PatrolState {
+        PatrolState { guard, visited: Visited::new(grid), obstruction: None }
+    }
+
+    fn is_obstacle(&self, grid: &Grid, (x, y): (i32, i32)) -> bool {
+        grid.is_obstacle(x, y) || self.obstruction == Some((x, y))
+    }
+}
+
+fn patrol(grid: &Grid, guard: Guard) -> Patrol {
+    let mut first_visits = Vec::new();
+    let mut state = PatrolState::new(grid, guard);
+    loop {
+        if !state.visited.insert(grid, state.guard.state()) {
+            return Patrol { first_visits, loops: true };
+        }
+        let (x, y) = state.guard.look_forward();
+        if !grid.contains(x, y) {
+            return Patrol { first_visits, loops: false };
+        }
+        if state.is_obstacle(grid, (x, y)) {
+            state.guard.turn();
+        } else {
+            if !state.visited.contains_cell(grid, (x, y)) {
+                first_visits.push(((x, y), state.guard));
+            }
+            state.guard.move_forward();
+        }
+    }
+}
+
+struct Game<'a> {
+    grid: &'a Grid,
+    state: PatrolState,
+    /// Tous les états du garde, dans l'ordre
+    path: Vec<State>,
     blocked: bool,
 }
 
-impl Game {
-    fn new(grid: Grid, guard: Guard) -> Game {
-        let mut visited = HashSet::new();
-        visited.insert(guard.position);
-        Game { grid, guard, visited, blocked: false }
+impl<'a> Game<'a> {
+    fn new(grid: &'a Grid, guard: Guard) -> Game<'a> {
+        Game { grid, state: PatrolState::new(grid, guard), path: Vec::new(), blocked: false }
+    }
+
+    /// Même partie avec un obstacle supplémentaire en `cell`
+    fn with_obstruction(mut self, cell: (i32, i32)) -> Game<'a> {
+        self.state.obstruction = Some(cell);
+        self
     }
 
     fn play(&mut self) {
-        let mut step_count = 0;
+        let state = &mut self.state;
         loop {
-            let next_position = self.guard.look_forward();
-            let next_cell = self.grid.get(next_position.0, next_position.1);
-            let this_cell = self.grid.get(self.guard.position.0, self.guard.position.1);
-            match this_cell {
-                Some('*') => {
-                    step_count += 1;
-                }
-                _ => {
-                    step_count = 0;
-                }
+            if !state.visited.insert(self.grid, state.guard.state()) {
+                self.blocked = true;
+                break;
             }
+            self.path.push(state.guard.state());
 
-            if step_count > 200 {
-                self.blocked = true;
+            let next_position = state.guard.look_forward();
+            if !self.grid.contains(next_position.0, next_position.1) {
+                // Si on sort du plateau, on arrête
                 break;
             }
+            if state.is_obstacle(self.grid, next_position) {
+                state.guard.turn();
+            } else {
+                state.guard.move_forward();
+            }
+        }
+    }
 
-            match next_cell {
-                Some('#') | Some('@') => {
-                    self.guard.turn_right();
-                }
-                Some(_) => {
-                    // avant de bouger, je modifie la cellule actuelle par '*'
-                    self.grid.grid[self.guard.position.1 as usize][self.guard.position.0 as usize] = '*';
-                    self.guard.move_forward();
-                    self.visited.insert(self.guard.position);
+    fn count_visited_cells(&self) -> usize {
+        self.state.visited.count_cells()
+    }
+
+    fn is_blocked(&self) -> bool {
+        self.blocked
+    }
+
+    fn path(&self) -> &[State] {
+        &self.path
+    }
+}
+
+/// Plusieurs gardes qui avancent au même rythme. À chaque pas, les gardes jouent chacun
+/// leur tour dans l'ordre de départ : un garde voit les autres à leur position la plus
+/// récente et les traite comme des obstacles, donc deux gardes n'occupent jamais la même case.
+/// Un garde sorti du plateau ne gêne plus personne.
+struct MultiGame<'a> {
+    grid: &'a Grid,
+    /// None une fois le garde sorti
+    guards: Vec<Option<Guard>>,
+    visited: Vec<HashSet<(i32, i32)>>,
+    /// États communs déjà rencontrés : en revoir un signifie que le système boucle
+    states: HashSet<Vec<Option<State>>>,
+    blocked: bool,
+}
+
+impl<'a> MultiGame<'a> {
+    fn new(grid: &'a Grid, guards: Vec<Guard>) -> MultiGame<'a> {
+        let visited = guards.iter().map(|guard| HashSet::from([guard.position])).collect();
+        let guards = guards.into_iter().map(Some).collect();
+        MultiGame { grid, guards, visited, states: HashSet::new(), blocked: false }
+    }
+
+    fn joint_state(&self) -> Vec<Option<State>> {
+        self.guards.iter().map(|guard| guard.map(|guard| guard.state())).collect()
+    }
+
+    fn is_occupied(&self, position: (i32, i32)) -> bool {
+        self.guards.iter().flatten().any(|guard| guard.position == position)
+    }
+
+    fn play(&mut self) {
+        while self.guards.iter().any(Option::is_some) {
+            if !self.states.insert(self.joint_state()) {
+                self.blocked = true;
+                break;
+            }
+            for i in 0..self.guards.len() {
+                let Some(mut guard) = self.guards[i] else {
+                    continue;
+                };
+                let (x, y) = guard.look_forward();
+                if !self.grid.contains(x, y) {
+                    self.guards[i] = None;
+                    continue;
                 }
-                None => {
-                    // Si on sort du plateau, on arrête
-                    break;
+                if self.grid.is_obstacle(x, y) || self.is_occupied((x, y)) {
+                    guard.turn();
+                } else {
+                    guard.move_forward();
+                    self.visited[i].insert(guard.position);
                 }
+                self.guards[i] = Some(guard);
             }
         }
     }
 
+    /// Nombre de cases visitées par au moins un garde
     fn count_visited_cells(&self) -> usize {
-        self.visited.len()
+        self.visited.iter().flatten().collect::<HashSet<_>>().len()
+    }
+
+    fn visited_cells_per_guard(&self) -> Vec<usize> {
+        self.visited.iter().map(HashSet::len).collect()
     }
 
     fn is_blocked(&self) -> bool {
@@ -134,73 +534,327 @@ impl Game {
     }
 }
 
-fn find_starting_position(grid: &Grid) -> (i32, i32) {
-    for y in 0..grid.grid.len() {
-        for x in 0..grid.grid[y].len() {
-            if grid.grid[y][x] == '^' {
-                return (x as i32, y as i32);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathFormat {
+    Json,
+    Csv,
+}
+
+impl FromStr for PathFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(PathFormat::Json),
+            "csv" => Ok(PathFormat::Csv),
+            _ => Err(format!("Unknown path format: {} (expected json or csv)", s)),
+        }
+    }
+}
+
+fn export_path(path: &[State], format: PathFormat) -> String {
+    match format {
+        PathFormat::Json => {
+            let steps: Vec<String> = path.iter()
+                .enumerate()
+                .map(|(step, &((x, y), direction))| {
+                    format!("  {{\"step\": {}, \"x\": {}, \"y\": {}, \"direction\": \"{}\"}}", step, x, y, direction.name())
+                })
+                .collect();
+            format!("[\n{}\n]\n", steps.join(",\n"))
+        }
+        PathFormat::Csv => {
+            let mut csv = String::from("step,x,y,direction\n");
+            for (step, &((x, y), direction)) in path.iter().enumerate() {
+                csv.push_str(&format!("{},{},{},{}\n", step, x, y, direction.name()));
             }
+            csv
         }
     }
-    panic!("No starting position found");
 }
 
-fn block(input: String) -> u64 {
-    let mut score_block: u64 = 0;
-    let grid = Grid::new(&input);
-    let starting_position = find_starting_position(&grid);
-    let total_cells = input.lines().count() * input.lines().next().unwrap_or("").len();
-    let pb = ProgressBar::new(total_cells as u64);
+/// Dessine le plateau après `step` états du trajet, comme dans l'énoncé :
+/// `|` et `-` pour les passages verticaux et horizontaux, `+` aux croisements et virages,
+/// et le garde avec `^ > v <`
+fn render_frame(grid: &Grid, path: &[State], step: usize, obstruction: Option<(i32, i32)>) -> String {
+    // Pour chaque case : (passage vertical, passage horizontal)
+    let mut trail: HashMap<(i32, i32), (bool, bool)> = HashMap::new();
+    for &(position, direction) in &path[..step] {
+        let marks = trail.entry(position).or_default();
+        if direction.is_vertical() {
+            marks.0 = true;
+        } else {
+            marks.1 = true;
+        }
+    }
+    let guard = path.get(step).copied();
 
-    for (y, line) in input.lines().enumerate() {
-        for (x, _ch) in line.chars().enumerate() {
-            if starting_position == (x as i32, (y as i32) + 1) {
-                continue;
-            }
-            let mut new_grid = grid.clone();
-            new_grid.grid[y][x] = '@';
-            let guard = Guard::new(starting_position, Direction::Up);
-            let mut game = Game::new(new_grid, guard);
-            game.play();
-            if game.is_blocked() {
-                score_block += 1;
+    let mut frame = String::new();
+    for y in 0..grid.height() as i32 {
+        for x in 0..grid.width() as i32 {
+            let cell = (x, y);
+            let glyph = match (guard, trail.get(&cell)) {
+                (Some((position, direction)), _) if position == cell => direction.glyph(),
+                _ if grid.is_obstacle(x, y) => '#',
+                _ if obstruction == Some(cell) => 'O',
+                (_, Some((true, true))) => '+',
+                (_, Some((true, false))) => '|',
+                (_, Some((false, true))) => '-',
+                _ => '.',
+            };
+            frame.push(glyph);
+        }
+        frame.push('\n');
+    }
+    frame
+}
+
+/// Anime le trajet du garde dans le terminal
+fn replay(grid: &Grid, path: &[State], obstruction: Option<(i32, i32)>, delay: Duration) {
+    for step in 0..=path.len() {
+        // Efface l'écran et replace le curseur en haut à gauche
+        print!("\x1b[2J\x1b[H{}", render_frame(grid, path, step, obstruction));
+        println!("Étape {}/{}", step, path.len());
+        thread::sleep(delay);
+    }
+}
+
+/// Position et direction de chaque garde, données par son symbole (`^ > v <`),
+/// dans l'ordre de lecture du plateau
+fn find_starting_positions(grid: &Grid) -> Vec<State> {
+    grid.starts.clone()
+}
+
+fn find_starting_position(grid: &Grid) -> Option<State> {
+    find_starting_positions(grid).into_iter().next()
+}
+
+/// Case où l'on peut ajouter un obstacle
+#[derive(Debug, PartialEq, Eq)]
+struct Candidate {
+    cell: (i32, i32),
+    /// État depuis lequel reprendre la simulation, juste avant que le garde n'atteigne la case.
+    /// None si la case est hors du trajet d'origine : l'obstacle ne change alors rien.
+    resume_from: Option<Guard>,
+}
+
+/// Une case ne peut pas recevoir d'obstacle si le garde s'y trouve au départ
+/// ou s'il y a déjà un obstacle
+fn is_excluded(grid: &Grid, starting_position: (i32, i32), cell: (i32, i32)) -> bool {
+    cell == starting_position || grid.is_obstacle(cell.0, cell.1)
+}
+
+/// Cases où un obstacle peut faire boucler le garde : celles du trajet d'origine, plus
+/// toutes les autres cases libres si le trajet d'origine boucle déjà
+fn candidate_obstructions(grid: &Grid, guard: Guard) -> Vec<Candidate> {
+    let starting_position = guard.position;
+    let patrol = patrol(grid, guard);
+    let mut candidates: Vec<Candidate> = patrol.first_visits
+        .iter()
+        .filter(|&&(cell, _)| !is_excluded(grid, starting_position, cell))
+        .map(|&(cell, guard)| Candidate { cell, resume_from: Some(guard) })
+        .collect();
+
+    if patrol.loops {
+        let on_path: HashSet<(i32, i32)> = patrol.first_visits.iter().map(|&(cell, _)| cell).collect();
+        for y in 0..grid.height() as i32 {
+            for x in 0..grid.width() as i32 {
+                let cell = (x, y);
+                if !on_path.contains(&cell) && !is_excluded(grid, starting_position, cell) {
+                    candidates.push(Candidate { cell, resume_from: None });
+                }
             }
-            pb.inc(1);
         }
     }
+    candidates
+}
+
+fn block(grid: &Grid, guard: Guard) -> u64 {
+    let jumps = JumpTable::new(grid);
+
+    // Un obstacle hors du trajet d'origine ne change rien : seules ces cases sont simulées
+    let candidates = candidate_obstructions(grid, guard);
+
+    #[cfg(feature = "progress")]
+    let pb = ProgressBar::new(candidates.len() as u64);
+
+    let score_block = candidates
+        .par_iter()
+        .filter(|candidate| {
+            #[cfg(feature = "progress")]
+            pb.inc(1);
+            match candidate.resume_from {
+                Some(guard) => loops_with_obstruction(&jumps, guard, candidate.cell),
+                None => true,
+            }
+        })
+        .count();
+
+    #[cfg(feature = "progress")]
     pb.finish_with_message("done");
-    score_block
+    score_block as u64
 }
 
-fn part_1() 
-{
+fn read_input() -> String {
     let current_dir = env::current_dir().expect("Failed to get current directory");
     let input_file_path = current_dir.join("inputs/input.txt");
-    let input = fs::read_to_string(input_file_path).expect("Failed to read input file");
+    fs::read_to_string(input_file_path).expect("Failed to read input file")
+}
+
+/// Retire l'option `name <valeur>` des arguments
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    let Some(i) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    if i + 1 >= args.len() {
+        return Err(format!("Missing value after {}", name));
+    }
+    let value = args.remove(i + 1);
+    args.remove(i);
+    Ok(Some(value))
+}
+
+/// Option `--turn <right|left|reverse|alternate>` (virage à droite par défaut)
+fn take_turn_policy(args: &mut Vec<String>) -> Result<TurnPolicy, String> {
+    take_option(args, "--turn")?.map_or(Ok(TurnPolicy::Right), |policy| policy.parse())
+}
 
-    let grid = Grid::new(&input);
-    let starting_position = find_starting_position(&grid);
+/// Option `--obstruction x,y` : obstacle supplémentaire pour la partie 1, l'export et le replay
+fn take_obstruction(args: &mut Vec<String>) -> Result<Option<(i32, i32)>, String> {
+    let Some(text) = take_option(args, "--obstruction")? else {
+        return Ok(None);
+    };
+    let cell = text.split_once(',').map(|(x, y)| (x.trim().parse(), y.trim().parse()));
+    match cell {
+        Some((Ok(x), Ok(y))) => Ok(Some((x, y))),
+        _ => Err(format!("Invalid cell: {} (expected x,y)", text)),
+    }
+}
 
-    let guard = Guard::new(starting_position, Direction::Up);
+fn play_part_1(grid: &Grid, guard: Guard, obstruction: Option<(i32, i32)>) -> Game<'_> {
     let mut game = Game::new(grid, guard);
+    if let Some(cell) = obstruction {
+        game = game.with_obstruction(cell);
+    }
     game.play();
+    game
+}
+
+fn part_1(grid: &Grid, guard: Guard, obstruction: Option<(i32, i32)>) {
+    let game = play_part_1(grid, guard, obstruction);
 
     let visited_cells = game.count_visited_cells();
     println!("Nombre de cellules visitées: {}", visited_cells);
+    if game.is_blocked() {
+        println!("Le garde tourne en boucle");
+    }
 }
 
-fn part_2() {
-    let current_dir = env::current_dir().expect("Failed to get current directory");
-    let input_file_path = current_dir.join("inputs/input.txt");
-    let input = fs::read_to_string(input_file_path).expect("Failed to read input file");
+/// Commande `guards` : tous les gardes du plateau patrouillent ensemble
+fn patrol_all(grid: &Grid, policy: TurnPolicy) {
+    let guards = find_starting_positions(grid)
+        .into_iter()
+        .map(|(position, direction)| Guard::new(position, direction).with_policy(policy))
+        .collect();
+    let mut game = MultiGame::new(grid, guards);
+    game.play();
 
-    let score_block = block(input);
+    println!("Nombre de cellules visitées: {}", game.count_visited_cells());
+    for (i, count) in game.visited_cells_per_guard().iter().enumerate() {
+        println!("Garde {}: {} cellules visitées", i + 1, count);
+    }
+    if game.is_blocked() {
+        println!("Les gardes tournent en boucle");
+    }
+}
+
+fn print_unbounded(patrol: &UnboundedPatrol) {
+    println!("Distance parcourue: {}", patrol.steps);
+    println!("Nombre de virages: {}", patrol.stops.len());
+    match patrol.outcome {
+        Outcome::Escapes(((x, y), direction)) => {
+            println!("Le garde s'éloigne à l'infini vers {} depuis ({}, {})", direction.name(), x, y)
+        }
+        Outcome::Loops => println!("Le garde tourne en boucle"),
+        Outcome::Drifts((dx, dy)) => println!("Le garde se décale de ({}, {}) à chaque cycle", dx, dy),
+    }
+}
+
+/// Commande `sparse <fichier>` : obstacles `x,y` sur un plan infini, garde `x,y ^`
+fn patrol_sparse(path: &str, policy: TurnPolicy) -> Result<(), String> {
+    let input = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let (map, guards) = SparseMap::parse(&input).map_err(|e| format!("{}, {}", path, e))?;
+    let &(position, direction) = guards.first().ok_or(format!("{}: no guard found (expected x,y ^)", path))?;
+    print_unbounded(&patrol_unbounded(&map, Guard::new(position, direction).with_policy(policy)));
+    Ok(())
+}
+
+fn patrol_world(world: &impl World, guard: Guard) {
+    print_unbounded(&patrol_unbounded(world, guard));
+}
+
+fn part_2(grid: &Grid, guard: Guard) {
+    let score_block = block(grid, guard);
     println!("Nombre de cellules bloquées: {}", score_block);
 }
 
 fn main() {
-    part_1();
-    part_2();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let policy = match take_turn_policy(&mut args) {
+        Ok(policy) => policy,
+        Err(e) => return eprintln!("{}", e),
+    };
+    let obstruction = match take_obstruction(&mut args) {
+        Ok(obstruction) => obstruction,
+        Err(e) => return eprintln!("{}", e),
+    };
+
+    // Carte creuse : l'entrée habituelle n'est pas lue
+    if args.first().map(String::as_str) == Some("sparse") {
+        let result = match args.get(1) {
+            Some(path) => patrol_sparse(path, policy),
+            None => Err("Usage: sparse <file>".to_string()),
+        };
+        if let Err(e) = result {
+            eprintln!("{}", e);
+        }
+        return;
+    }
+
+    let grid = Grid::new(&read_input());
+    let Some((starting_position, direction)) = find_starting_position(&grid) else {
+        return eprintln!("No starting position found (expected one of ^ > v <)");
+    };
+    let guard = Guard::new(starting_position, direction).with_policy(policy);
+
+    match args.first().map(String::as_str) {
+        // `export <json|csv>` : trajet de la partie 1 sur la sortie standard
+        Some("export") => {
+            let format = match args.get(1).map(|arg| arg.parse::<PathFormat>()) {
+                Some(Ok(format)) => format,
+                Some(Err(e)) => return eprintln!("{}", e),
+                None => return eprintln!("Usage: export <json|csv>"),
+            };
+            print!("{}", export_path(play_part_1(&grid, guard, obstruction).path(), format));
+        }
+        // `replay [délai en ms]` : animation du trajet de la partie 1
+        Some("replay") => {
+            let delay = match args.get(1).map(|arg| arg.parse::<u64>()) {
+                Some(Ok(delay)) => delay,
+                Some(Err(_)) => return eprintln!("Invalid delay: {}", args[1]),
+                None => 50,
+            };
+            let game = play_part_1(&grid, guard, obstruction);
+            replay(&grid, game.path(), obstruction, Duration::from_millis(delay));
+        }
+        Some("guards") => patrol_all(&grid, policy),
+        // `tiled` : le plateau se répète à l'infini
+        Some("tiled") => patrol_world(&TiledMap::new(&grid), guard),
+        _ => {
+            part_1(&grid, guard, obstruction);
+            part_2(&grid, guard);
+        }
+    }
 }
 
 
@@ -208,6 +862,23 @@ fn main() {
 mod tests {
     use super::*;
 
+    /// Garde trouvé sur le plateau, avec la politique par défaut
+    fn start(grid: &Grid) -> Guard {
+        let (position, direction) = find_starting_position(grid).unwrap();
+        Guard::new(position, direction)
+    }
+
+    fn path_of(input: &str) -> Vec<State> {
+        let grid = Grid::new(input);
+        let guard = start(&grid);
+        play_part_1(&grid, guard, None).path().to_vec()
+    }
+
+    fn block_input(input: &str) -> u64 {
+        let grid = Grid::new(input);
+        block(&grid, start(&grid))
+    }
+
     #[test]
     fn test_part_1() {
         let input = "....#.....
@@ -221,11 +892,10 @@ mod tests {
 #.........
 ......#...";
         let grid = Grid::new(input);
-        let starting_position = find_starting_position(&grid);
-        assert_eq!(starting_position, (4, 6));
+        assert_eq!(find_starting_position(&grid), Some(((4, 6), Direction::Up)));
 
-        let guard = Guard::new(starting_position, Direction::Up);
-        let mut game = Game::new(grid, guard);
+        let guard = start(&grid);
+        let mut game = Game::new(&grid, guard);
         game.play();
         assert_eq!(game.count_visited_cells(), 41);
     }
@@ -242,8 +912,340 @@ mod tests {
 ........#.
 #.........
 ......#...";
-        let score_block = block(input.to_string());
+        let score_block = block_input(input);
         assert_eq!(score_block, 6);
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_long_retraced_corridor_is_not_a_loop() {
+        // Le garde monte un couloir de 250 cases, fait demi-tour en haut puis ressort par le bas :
+        // il repasse sur plus de 200 cases déjà visitées sans pour autant boucler
+        let height = 252;
+        let mut lines = vec![".#.".to_string(), "..#".to_string()];
+        lines.extend((2..height - 1).map(|_| "...".to_string()));
+        lines.push(".^.".to_string());
+        let input = lines.join("\n");
+
+        let grid = Grid::new(&input);
+        let guard = start(&grid);
+        let mut game = Game::new(&grid, guard);
+        game.play();
+        assert!(!game.is_blocked());
+        assert_eq!(game.count_visited_cells(), height - 1);
+    }
+
+    #[test]
+    fn test_loop_detection() {
+        let input = ".#..
+...#
+#^..
+..#.";
+        let grid = Grid::new(input);
+        let guard = start(&grid);
+        let mut game = Game::new(&grid, guard);
+        game.play();
+        assert!(game.is_blocked());
+    }
+
+    #[test]
+    fn test_jump_table() {
+        let input = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+        let grid = Grid::new(input);
+        let jumps = JumpTable::new(&grid);
+        assert_eq!(jumps.stop((4, 6), Direction::Up), Some((4, 1)));
+        assert_eq!(jumps.stop((4, 1), Direction::Right), Some((8, 1)));
+        assert_eq!(jumps.stop((4, 6), Direction::Left), Some((2, 6)));
+        assert_eq!(jumps.stop((4, 6), Direction::Down), None);
+        assert_eq!(jumps.stop((0, 0), Direction::Up), None);
+    }
+
+    #[test]
+    fn test_loops_with_obstruction() {
+        let input = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+        let grid = Grid::new(input);
+        let jumps = JumpTable::new(&grid);
+        let guard = start(&grid);
+        assert!(loops_with_obstruction(&jumps, guard, (3, 6)));
+        assert!(loops_with_obstruction(&jumps, guard, (7, 9)));
+        assert!(!loops_with_obstruction(&jumps, guard, (0, 0)));
+        assert!(!loops_with_obstruction(&jumps, guard, (4, 5)));
+    }
+
+    #[test]
+    fn test_candidate_obstructions_exclusions() {
+        let input = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+        let grid = Grid::new(input);
+        let guard = start(&grid);
+        let starting_position = guard.position;
+        let candidates = candidate_obstructions(&grid, guard);
+        assert_eq!(candidates.len(), 40);
+        assert!(candidates.iter().all(|candidate| candidate.cell != starting_position));
+        // La case juste devant le garde est une case comme les autres
+        assert_eq!(candidates[0], Candidate { cell: (4, 5), resume_from: Some(guard) });
+
+        // Trajet d'origine en boucle : les cases hors trajet sont candidates, sauf le départ et les '#'
+        let input = ".#..
+...#
+#^..
+..#.";
+        let grid = Grid::new(input);
+        let guard = start(&grid);
+        let starting_position = guard.position;
+        let candidates = candidate_obstructions(&grid, guard);
+        assert!(candidates.iter().all(|candidate| {
+            candidate.cell != starting_position && !grid.is_obstacle(candidate.cell.0, candidate.cell.1)
+        }));
+        assert_eq!(candidates.len(), 16 - 4 - 1);
+    }
+
+    #[test]
+    fn test_block_in_front_of_start() {
+        // Un obstacle juste devant le garde le fait tourner dans le rectangle formé par les '#'
+        let input = ".....
+.....
+.^..#
+.....
+#....
+...#.";
+        assert_eq!(block_input(input), 1);
+    }
+
+    #[test]
+    fn test_path() {
+        let input = "..#.
+....
+.^..";
+        let path = path_of(input);
+        assert_eq!(path, &[
+            ((1, 2), Direction::Up),
+            ((1, 1), Direction::Up),
+            ((1, 0), Direction::Up),
+        ]);
+
+        // Les virages sont des états à part entière
+        let input = ".#..
+...#
+.^..";
+        let path = path_of(input);
+        assert_eq!(path, &[
+            ((1, 2), Direction::Up),
+            ((1, 1), Direction::Up),
+            ((1, 1), Direction::Right),
+            ((2, 1), Direction::Right),
+            ((2, 1), Direction::Down),
+            ((2, 2), Direction::Down),
+        ]);
+        assert_eq!(
+            export_path(&path, PathFormat::Csv),
+            "step,x,y,direction\n0,1,2,up\n1,1,1,up\n2,1,1,right\n3,2,1,right\n4,2,1,down\n5,2,2,down\n"
+        );
+        assert_eq!(
+            export_path(&path[..2], PathFormat::Json),
+            "[\n  {\"step\": 0, \"x\": 1, \"y\": 2, \"direction\": \"up\"},\n  \
+             {\"step\": 1, \"x\": 1, \"y\": 1, \"direction\": \"up\"}\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_render_frame() {
+        let input = ".#..
+...#
+.^..";
+        let path = path_of(input);
+        let grid = Grid::new(input);
+        assert_eq!(render_frame(&grid, &path, 0, None), ".#..\n...#\n.^..\n");
+        assert_eq!(render_frame(&grid, &path, 3, None), ".#..\n.+>#\n.|..\n");
+        assert_eq!(render_frame(&grid, &path, 6, None), ".#..\n.++#\n.||.\n");
+        assert_eq!(render_frame(&grid, &path, 0, Some((3, 2))), ".#..\n...#\n.^.O\n");
+    }
+
+    #[test]
+    fn test_starting_glyph() {
+        let input = "....
+.>.#
+....";
+        let grid = Grid::new(input);
+        assert_eq!(find_starting_position(&grid), Some(((1, 1), Direction::Right)));
+        assert_eq!(find_starting_position(&Grid::new("..\n..")), None);
+
+        // Le garde part vers la droite, tourne vers le bas devant le '#' et sort
+        let mut game = Game::new(&grid, start(&grid));
+        game.play();
+        assert_eq!(game.path().last(), Some(&((2, 2), Direction::Down)));
+        assert_eq!(game.count_visited_cells(), 3);
+    }
+
+    #[test]
+    fn test_turn_policies() {
+        assert_eq!(TurnPolicy::Left.turn(Direction::Up, 0), Direction::Left);
+        assert_eq!(TurnPolicy::Reverse.turn(Direction::Left, 0), Direction::Right);
+        assert_eq!(TurnPolicy::Alternate.turn(Direction::Up, 0), Direction::Right);
+        assert_eq!(TurnPolicy::Alternate.turn(Direction::Right, 1), Direction::Up);
+        assert_eq!("alternate".parse::<TurnPolicy>(), Ok(TurnPolicy::Alternate));
+        assert!("around".parse::<TurnPolicy>().is_err());
+
+        let input = ".#..
+...#
+.^..";
+        let grid = Grid::new(input);
+        let (position, direction) = find_starting_position(&grid).unwrap();
+
+        // À gauche, le garde sort par la gauche juste après le premier obstacle
+        let mut game = Game::new(&grid, Guard::new(position, direction).with_policy(TurnPolicy::Left));
+        game.play();
+        assert_eq!(game.path().last(), Some(&((0, 1), Direction::Left)));
+        assert!(!game.is_blocked());
+
+        // Demi-tour : il redescend la colonne et sort par le bas
+        let mut game = Game::new(&grid, Guard::new(position, direction).with_policy(TurnPolicy::Reverse));
+        game.play();
+        assert_eq!(game.path().last(), Some(&((1, 2), Direction::Down)));
+        assert_eq!(game.count_visited_cells(), 2);
+    }
+
+    #[test]
+    fn test_alternate_policy() {
+        // Droite puis gauche : le garde monte en escalier vers la droite et finit par sortir
+        let input = ".#...
+....#
+.^...
+.....";
+        let grid = Grid::new(input);
+        let (position, direction) = find_starting_position(&grid).unwrap();
+        let guard = Guard::new(position, direction).with_policy(TurnPolicy::Alternate);
+        let mut game = Game::new(&grid, guard);
+        game.play();
+        assert!(!game.is_blocked());
+        assert_eq!(game.path().last(), Some(&((3, 0), Direction::Up)));
+        assert!(!patrol(&grid, guard).loops);
+    }
+
+    #[test]
+    fn test_multiple_guards() {
+        let input = "...
+>.<
+...";
+        let grid = Grid::new(input);
+        let starts = find_starting_positions(&grid);
+        assert_eq!(starts, vec![((0, 1), Direction::Right), ((2, 1), Direction::Left)]);
+
+        // Le premier garde avance au centre ; le second le trouve devant lui et tourne vers le haut.
+        // Au pas suivant, le premier bute à son tour sur le second et part vers le bas.
+        let guards = starts.iter().map(|&(position, direction)| Guard::new(position, direction)).collect();
+        let mut game = MultiGame::new(&grid, guards);
+        game.play();
+        assert!(!game.is_blocked());
+        assert_eq!(game.visited_cells_per_guard(), vec![3, 2]);
+        assert_eq!(game.count_visited_cells(), 5);
+    }
+
+    #[test]
+    fn test_multiple_guards_single_guard_matches_game() {
+        let input = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+        let grid = Grid::new(input);
+        let mut game = MultiGame::new(&grid, vec![start(&grid)]);
+        game.play();
+        assert_eq!(game.count_visited_cells(), 41);
+        assert_eq!(game.visited_cells_per_guard(), vec![41]);
+    }
+
+    #[test]
+    fn test_multiple_guards_loop() {
+        // Deux gardes qui font demi-tour entre deux murs se renvoient l'un l'autre indéfiniment
+        let input = "#>.<#";
+        let grid = Grid::new(input);
+        let guards = find_starting_positions(&grid)
+            .into_iter()
+            .map(|(position, direction)| Guard::new(position, direction).with_policy(TurnPolicy::Reverse))
+            .collect();
+        let mut game = MultiGame::new(&grid, guards);
+        game.play();
+        assert!(game.is_blocked());
+        assert_eq!(game.visited_cells_per_guard(), vec![2, 2]);
+        assert_eq!(game.count_visited_cells(), 3);
+
+        // Un garde enfermé suffit à faire boucler le système, même si l'autre sort
+        let input = ".#...
+...#.
+#^...
+..#.v";
+        let grid = Grid::new(input);
+        let guards = find_starting_positions(&grid)
+            .into_iter()
+            .map(|(position, direction)| Guard::new(position, direction))
+            .collect();
+        let mut game = MultiGame::new(&grid, guards);
+        game.play();
+        assert!(game.is_blocked());
+        assert_eq!(game.visited_cells_per_guard(), vec![4, 1]);
+    }
+
+    #[test]
+    fn test_shared_grid_with_obstruction() {
+        let input = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+        // Toutes les simulations partagent le même plateau, qui n'est jamais modifié
+        let grid = Grid::new(input);
+        let guard = start(&grid);
+        let jumps = JumpTable::new(&grid);
+        let mut loops = 0;
+        for y in 0..grid.height() as i32 {
+            for x in 0..grid.width() as i32 {
+                if is_excluded(&grid, guard.position, (x, y)) {
+                    continue;
+                }
+                let mut game = Game::new(&grid, guard).with_obstruction((x, y));
+                game.play();
+                assert_eq!(game.is_blocked(), loops_with_obstruction(&jumps, guard, (x, y)));
+                loops += game.is_blocked() as u64;
+            }
+        }
+        assert_eq!(loops, block(&grid, guard));
+        assert_eq!(play_part_1(&grid, guard, None).count_visited_cells(), 41);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use crate::{Direction, Grid, Guard, State};
+
+/// Monde sans bord : le garde n'en sort jamais, il peut seulement s'éloigner à l'infini
+/// lorsqu'il n'y a plus aucun obstacle devant lui
+pub trait World {
+    /// Case où le garde s'arrête devant le prochain obstacle, ou None s'il n'y en a aucun
+    fn next_stop(&self, position: (i32, i32), direction: Direction) -> Option<(i32, i32)>;
+
+    /// Position ramenée dans le motif de référence, pour reconnaître un même état
+    /// à une translation près
+    fn canonical(&self, position: (i32, i32)) -> (i32, i32) {
+        position
+    }
+}
+
+/// Obstacles donnés par leurs coordonnées, sur un plan vide et infini.
+/// Chaque rangée et chaque colonne garde ses obstacles triés : trouver le prochain
+/// est une recherche dichotomique, quelle que soit la distance.
+pub struct SparseMap {
+    /// Rangée -> abscisses des obstacles
+    rows: HashMap<i32, Vec<i32>>,
+    /// Colonne -> ordonnées des obstacles
+    columns: HashMap<i32, Vec<i32>>,
+}
+
+impl SparseMap {
+    pub fn new(obstacles: &[(i32, i32)]) -> SparseMap {
+        let mut rows: HashMap<i32, Vec<i32>> = HashMap::new();
+        let mut columns: HashMap<i32, Vec<i32>> = HashMap::new();
+        for &(x, y) in obstacles {
+            rows.entry(y).or_default().push(x);
+            columns.entry(x).or_default().push(y);
+        }
+        for line in rows.values_mut().chain(columns.values_mut()) {
+            line.sort_unstable();
+            line.dedup();
+        }
+        SparseMap { rows, columns }
+    }
+
+    /// Lit une liste d'obstacles `x,y`, un par ligne. Une ligne `x,y ^` (ou `> v <`)
+    /// place un garde. Les lignes vides sont ignorées.
+    pub fn parse(input: &str) -> Result<(SparseMap, Vec<State>), String> {
+        let mut obstacles = Vec::new();
+        let mut guards = Vec::new();
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = || format!("line {}: expected x,y or x,y followed by ^ > v <, found {:?}", i + 1, line);
+            let (cell, glyph) = match line.split_once(char::is_whitespace) {
+                Some((cell, glyph)) => (cell, Some(glyph.trim())),
+                None => (line, None),
+            };
+            let (x, y) = cell.split_once(',').ok_or_else(error)?;
+            let cell = match (x.trim().parse(), y.trim().parse()) {
+                (Ok(x), Ok(y)) => (x, y),
+                _ => return Err(error()),
+            };
+            match glyph {
+                None => obstacles.push(cell),
+                Some(glyph) => {
+                    let mut chars = glyph.chars();
+                    let direction = match (chars.next().and_then(Direction::from_glyph), chars.next()) {
+                        (Some(direction), None) => direction,
+                        _ => return Err(error()),
+                    };
+                    guards.push((cell, direction));
+                }
+            }
+        }
+        Ok((SparseMap::new(&obstacles), guards))
+    }
+}
+
+/// Case avant le prochain obstacle d'une ligne triée, en partant de `from`
+fn stop_in_line(line: Option<&Vec<i32>>, from: i32, forward: bool) -> Option<i32> {
+    let line = line?;
+    if forward {
+        line.get(line.partition_point(|&v| v <= from)).map(|&v| v - 1)
+    } else {
+        line.partition_point(|&v| v < from).checked_sub(1).map(|i| line[i] + 1)
+    }
+}
+
+impl World for SparseMap {
+    fn next_stop(&self, (x, y): (i32, i32), direction: Direction) -> Option<(i32, i32)> {
+        match direction {
+            Direction::Up => stop_in_line(self.columns.get(&x), y, false).map(|y| (x, y)),
+            Direction::Down => stop_in_line(self.columns.get(&x), y, true).map(|y| (x, y)),
+            Direction::Left => stop_in_line(self.rows.get(&y), x, false).map(|x| (x, y)),
+            Direction::Right => stop_in_line(self.rows.get(&y), x, true).map(|x| (x, y)),
+        }
+    }
+}
+
+/// Plateau répété à l'infini dans les deux directions
+pub struct TiledMap<'a> {
+    grid: &'a Grid,
+}
+
+impl<'a> TiledMap<'a> {
+    /// Le plateau ne doit pas être vide
+    pub fn new(grid: &'a Grid) -> TiledMap<'a> {
+        TiledMap { grid }
+    }
+
+    fn is_obstacle(&self, (x, y): (i32, i32)) -> bool {
+        let (x, y) = self.canonical((x, y));
+        self.grid.is_obstacle(x, y)
+    }
+}
+
+impl World for TiledMap<'_> {
+    fn next_stop(&self, position: (i32, i32), direction: Direction) -> Option<(i32, i32)> {
+        let (dx, dy) = direction.delta();
+        // Après une période complète sans obstacle, la ligne n'en contient aucun
+        let period = if dx == 0 { self.grid.height() } else { self.grid.width() };
+        let mut position = position;
+        for _ in 0..period {
+            let next = (position.0 + dx, position.1 + dy);
+            if self.is_obstacle(next) {
+                return Some(position);
+            }
+            position = next;
+        }
+        None
+    }
+
+    fn canonical(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (x.rem_euclid(self.grid.width() as i32), y.rem_euclid(self.grid.height() as i32))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Plus aucun obstacle devant : le garde s'éloigne à l'infini depuis cet état
+    Escapes(State),
+    /// Le garde repasse exactement par le même état
+    Loops,
+    /// Le garde refait le même trajet, décalé de ce vecteur à chaque cycle
+    Drifts((i32, i32)),
+}
+
+pub struct UnboundedPatrol {
+    /// Cases où le garde s'arrête devant un obstacle, avec sa direction d'arrivée
+    pub stops: Vec<State>,
+    /// Nombre de cases parcourues
+    pub steps: u64,
+    pub outcome: Outcome,
+}
+
+/// Patrouille d'obstacle en obstacle, sans jamais parcourir les cases une à une
+pub fn patrol_unbounded(world: &impl World, mut guard: Guard) -> UnboundedPatrol {
+    // État ramené dans le motif -> position réelle à laquelle il a été vu
+    let mut seen: HashMap<State, (i32, i32)> = HashMap::new();
+    let mut stops = Vec::new();
+    let mut steps = 0;
+    loop {
+        let key = (world.canonical(guard.position), guard.direction);
+        if let Some(&previous) = seen.get(&key) {
+            let outcome = if previous == guard.position {
+                Outcome::Loops
+            } else {
+                Outcome::Drifts((guard.position.0 - previous.0, guard.position.1 - previous.1))
+            };
+            return UnboundedPatrol { stops, steps, outcome };
+        }
+        seen.insert(key, guard.position);
+
+        let Some(stop) = world.next_stop(guard.position, guard.direction) else {
+            return UnboundedPatrol { stops, steps, outcome: Outcome::Escapes(guard.state()) };
+        };
+        steps += (stop.0.abs_diff(guard.position.0) + stop.1.abs_diff(guard.position.1)) as u64;
+        guard.position = stop;
+        stops.push(guard.state());
+        guard.turn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Game, TurnPolicy};
+
+    const EXAMPLE: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    #[test]
+    fn test_sparse_map_matches_grid() {
+        let grid = Grid::new(EXAMPLE);
+        let (position, direction) = grid.starts[0];
+        let guard = Guard::new(position, direction);
+        let mut obstacles: Vec<(i32, i32)> = (0..10)
+            .flat_map(|y| (0..10).map(move |x| (x, y)))
+            .filter(|&(x, y)| grid.is_obstacle(x, y))
+            .collect();
+        let patrol = patrol_unbounded(&SparseMap::new(&obstacles), guard);
+
+        // Sortir du plateau revient à ne plus avoir d'obstacle devant soi
+        let mut game = Game::new(&grid, guard);
+        game.play();
+        let turns: Vec<State> = game.path()
+            .windows(2)
+            .filter(|states| states[0].0 == states[1].0)
+            .map(|states| states[0])
+            .collect();
+        assert_eq!(patrol.stops, turns);
+        assert_eq!(patrol.outcome, Outcome::Escapes(((7, 7), Direction::Down)));
+
+        // Avec l'obstacle ajouté de l'énoncé, le garde boucle
+        obstacles.push((3, 6));
+        assert_eq!(patrol_unbounded(&SparseMap::new(&obstacles), guard).outcome, Outcome::Loops);
+    }
+
+    #[test]
+    fn test_sparse_map_large_coordinates() {
+        let map = SparseMap::new(&[(0, -3_000_000), (2_000_000, -2_999_999), (1_999_999, 1_000_000)]);
+        let patrol = patrol_unbounded(&map, Guard::new((0, 0), Direction::Up));
+        assert_eq!(patrol.stops, vec![
+            ((0, -2_999_999), Direction::Up),
+            ((1_999_999, -2_999_999), Direction::Right),
+            ((1_999_999, 999_999), Direction::Down),
+        ]);
+        assert_eq!(patrol.outcome, Outcome::Escapes(((1_999_999, 999_999), Direction::Left)));
+        assert_eq!(patrol.steps, 2_999_999 + 1_999_999 + 3_999_998);
+    }
+
+    #[test]
+    fn test_parse_sparse_map() {
+        let (map, guards) = SparseMap::parse("0,-3\n\n 2,1 \n0,0 ^\n").unwrap();
+        assert_eq!(guards, vec![((0, 0), Direction::Up)]);
+        assert_eq!(map.next_stop((0, 0), Direction::Up), Some((0, -2)));
+        assert_eq!(map.next_stop((0, 1), Direction::Right), Some((1, 1)));
+        assert_eq!(map.next_stop((3, 1), Direction::Right), None);
+
+        assert!(SparseMap::parse("1;2").err().unwrap().starts_with("line 1:"));
+        assert!(SparseMap::parse("1,2\n3,4 ^^").err().unwrap().starts_with("line 2:"));
+        assert!(SparseMap::parse("1,2 #").is_err());
+    }
+
+    #[test]
+    fn test_tiled_map() {
+        let grid = Grid::new("#..\n^.#\n.#.");
+        let tiled = TiledMap::new(&grid);
+        assert_eq!(tiled.next_stop((0, 1), Direction::Right), Some((1, 1)));
+        assert_eq!(tiled.next_stop((1, 1), Direction::Up), Some((1, 0)));
+        assert_eq!(tiled.next_stop((6, -4), Direction::Left), Some((5, -4)));
+
+        // En alternant droite et gauche, le garde monte en escalier d'un motif à l'autre
+        let (position, direction) = grid.starts[0];
+        let guard = Guard::new(position, direction).with_policy(TurnPolicy::Alternate);
+        let patrol = patrol_unbounded(&tiled, guard);
+        assert_eq!(patrol.outcome, Outcome::Drifts((3, -3)));
+        assert_eq!(patrol.steps, 6);
+
+        // Coincé entre deux obstacles, il tourne sur place
+        let grid = Grid::new("#^\n.#");
+        let (position, direction) = grid.starts[0];
+        let guard = Guard::new(position, direction).with_policy(TurnPolicy::Alternate);
+        assert_eq!(patrol_unbounded(&TiledMap::new(&grid), guard).outcome, Outcome::Loops);
+
+        // Une ligne sans obstacle laisse le garde partir
+        let grid = Grid::new("#.\n.^");
+        let (position, direction) = grid.starts[0];
+        let patrol = patrol_unbounded(&TiledMap::new(&grid), Guard::new(position, direction));
+        assert_eq!(patrol.outcome, Outcome::Escapes(((1, 1), Direction::Up)));
+    }
+}
@@ -0,0 +1,55 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_07::{generate_results, is_solvable, Add, Concat, Equation, Mul, Operator, Value};
+
+const EQUATIONS: usize = 20;
+const OPERATORS: [&dyn Operator; 3] = [&Add, &Mul, &Concat];
+
+// Pseudo-random generator (LCG) for reproducible inputs
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+}
+
+/// Equations with 12 to 14 single-digit operands, so that no result overflows.
+/// Every other target is reachable.
+fn synthetic_equations() -> Vec<Equation> {
+    let mut rng = Lcg(2024);
+    (0..EQUATIONS)
+        .map(|line| {
+            let numbers: Vec<Value> = (0..12 + rng.next(3)).map(|_| Value::from(1 + rng.next(9))).collect();
+            let mut target = numbers[0];
+            for &number in &numbers[1..] {
+                target = OPERATORS[rng.next(3) as usize].apply(target, number).unwrap();
+            }
+            if line % 2 == 1 {
+                target += 1;
+            }
+            Equation { line: line + 1, target, numbers }
+        })
+        .collect()
+}
+
+fn benchmark_solvers(c: &mut Criterion) {
+    let equations = synthetic_equations();
+
+    let mut group = c.benchmark_group("solve_12_plus_operands");
+    group.sample_size(10);
+    group.bench_function("generate_results", |b| b.iter(|| {
+        black_box(equations.iter()
+            .filter(|equation| generate_results(&equation.numbers, &OPERATORS).contains(&equation.target))
+            .count());
+    }));
+    group.bench_function("is_solvable", |b| b.iter(|| {
+        black_box(equations.iter()
+            .filter(|equation| is_solvable(equation.target, &equation.numbers, &OPERATORS))
+            .count());
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_solvers);
+criterion_main!(benches);
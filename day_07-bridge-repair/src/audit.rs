@@ -0,0 +1,82 @@
+use std::fmt;
+
+use crate::equation::Equation;
+use crate::operator::Operator;
+use crate::solver::{format_expression, solve, solve_all};
+
+/// Operator sequences found for one equation: at most one unless every solution was asked for
+pub struct Audit<'a> {
+    pub equation: &'a Equation,
+    pub solutions: Vec<Vec<&'a dyn Operator>>,
+}
+
+pub fn audit<'a>(equations: &'a [Equation], operators: &[&'a dyn Operator], all: bool) -> Vec<Audit<'a>> {
+    equations
+        .iter()
+        .map(|equation| {
+            let solutions = if all {
+                solve_all(equation.target, &equation.numbers, operators)
+            } else {
+                solve(equation.target, &equation.numbers, operators).into_iter().collect()
+            };
+            Audit { equation, solutions }
+        })
+        .collect()
+}
+
+impl fmt::Display for Audit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Equation { line, target, numbers } = self.equation;
+        match &self.solutions[..] {
+            [] => writeln!(f, "line {}: {} has no solution", line, target),
+            [solution] => writeln!(f, "line {}: {} = {}", line, target, format_expression(numbers, solution)),
+            solutions => {
+                writeln!(f, "line {}: {} has {} solutions", line, target, solutions.len())?;
+                for solution in solutions {
+                    writeln!(f, "    {} = {}", target, format_expression(numbers, solution))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// One row per solution, or a single row with an empty expression for an unsolvable line
+pub fn export_csv(audits: &[Audit]) -> String {
+    let mut csv = String::from("line,target,solutions,expression\n");
+    for audit in audits {
+        let Equation { line, target, numbers } = audit.equation;
+        let count = audit.solutions.len();
+        if audit.solutions.is_empty() {
+            csv.push_str(&format!("{},{},0,\n", line, target));
+        }
+        for solution in &audit.solutions {
+            csv.push_str(&format!("{},{},{},{}\n", line, target, count, format_expression(numbers, solution)));
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::parse_equations;
+    use crate::operator::{Add, Mul};
+
+    #[test]
+    fn test_audit() {
+        let equations = parse_equations("190: 10 19\n83: 17 5\n3267: 81 40 27\n").equations;
+        let operators: [&dyn Operator; 2] = [&Add, &Mul];
+
+        let audits = audit(&equations, &operators, false);
+        let report: String = audits.iter().map(|audit| audit.to_string()).collect();
+        assert_eq!(report, "line 1: 190 = 10 * 19\nline 2: 83 has no solution\nline 3: 3267 = 81 * 40 + 27\n");
+
+        let audits = audit(&equations, &operators, true);
+        assert_eq!(audits[2].to_string(), "line 3: 3267 has 2 solutions\n    3267 = 81 * 40 + 27\n    3267 = 81 + 40 * 27\n");
+        assert_eq!(
+            export_csv(&audits),
+            "line,target,solutions,expression\n1,190,1,10 * 19\n2,83,0,\n3,3267,2,81 * 40 + 27\n3,3267,2,81 + 40 * 27\n"
+        );
+    }
+}
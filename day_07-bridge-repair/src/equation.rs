@@ -0,0 +1,107 @@
+use std::fmt;
+use std::fs;
+use std::io;
+
+use crate::operator::Value;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Equation {
+    /// Line number in the input file, starting at 1
+    pub line: usize,
+    pub target: Value,
+    pub numbers: Vec<Value>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number in the input file, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Equations read from a file, and the lines that could not be read
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    pub equations: Vec<Equation>,
+    pub errors: Vec<ParseError>,
+}
+
+impl fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_value(text: &str, what: &str) -> Result<Value, String> {
+    text.parse().map_err(|_| format!("invalid {} {:?}", what, text))
+}
+
+fn parse_equation(text: &str, line: usize) -> Result<Equation, String> {
+    let (target, numbers) = text.split_once(':')
+        .ok_or_else(|| format!("expected `target: numbers`, found {:?}", text))?;
+    let target = parse_value(target.trim(), "target")?;
+    let numbers = numbers.split_whitespace()
+        .map(|number| parse_value(number, "number"))
+        .collect::<Result<Vec<Value>, String>>()?;
+    if numbers.is_empty() {
+        return Err("no numbers after the target".to_string());
+    }
+    Ok(Equation { line, target, numbers })
+}
+
+/// Reads one equation per line. Blank lines are skipped; any other line that cannot be read
+/// is reported without stopping the others.
+pub fn parse_equations(input: &str) -> ParseReport {
+    let mut report = ParseReport::default();
+    for (i, text) in input.lines().enumerate() {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match parse_equation(text, i + 1) {
+            Ok(equation) => report.equations.push(equation),
+            Err(message) => report.errors.push(ParseError { line: i + 1, message }),
+        }
+    }
+    report
+}
+
+pub fn parse_file(filename: &str) -> io::Result<ParseReport> {
+    Ok(parse_equations(&fs::read_to_string(filename)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_equations() {
+        let equations = parse_equations("190: 10 19\n\n3267: 81 40 27\n").equations;
+        assert_eq!(equations, vec![
+            Equation { line: 1, target: 190, numbers: vec![10, 19] },
+            Equation { line: 3, target: 3267, numbers: vec![81, 40, 27] },
+        ]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let report = parse_equations("190: 10 19\n7290 6 8\nx: 1 2\n12: 3 -4\n5:\n \n7: 7\n");
+        assert_eq!(report.equations, vec![
+            Equation { line: 1, target: 190, numbers: vec![10, 19] },
+            Equation { line: 7, target: 7, numbers: vec![7] },
+        ]);
+        let lines: Vec<usize> = report.errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, vec![2, 3, 4, 5]);
+        assert_eq!(report.errors[2].to_string(), "line 4: invalid number \"-4\"");
+        assert_eq!(report.to_string().lines().count(), 4);
+    }
+}
@@ -0,0 +1,11 @@
+pub mod audit;
+pub mod equation;
+pub mod operator;
+pub mod precedence;
+pub mod solver;
+
+pub use audit::{audit, export_csv, Audit};
+pub use equation::{parse_equations, parse_file, Equation, ParseError, ParseReport};
+pub use operator::{parse_operators, Add, Concat, Inverse, Mul, Operator, Pow, Preimage, Sub, Value, OPERATORS};
+pub use precedence::{evaluate_with_precedence, solve_with_parentheses, solve_with_precedence, Evaluation};
+pub use solver::{calibration_result, format_expression, generate_results, is_solvable, solve, solve_all};
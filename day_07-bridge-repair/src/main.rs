@@ -1,95 +1,75 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::env;
 
-fn parse_file_to_hmap(filename: &str) -> io::Result<HashMap<u64, Vec<u64>>> {
-    let mut hmap: HashMap<u64, Vec<u64>> = HashMap::new();
-    let file = File::open(filename)?;
-    for line in io::BufReader::new(file).lines() {
-        let line = line?;
-        let parts: Vec<&str> = line.split(": ").collect();
-        if parts.len() == 2 {
-            let key: u64 = parts[0].parse().unwrap();
-            let values: Vec<u64> = parts[1]
-                .split_whitespace()
-                .map(|v| v.parse().unwrap())
-                .collect();
-            hmap.insert(key, values);
-        }
-    }
-    Ok(hmap)
-}
+use day_07::{
+    audit, calibration_result, export_csv, parse_file, parse_operators, Add, Concat, Evaluation, Mul, Operator, Value,
+};
 
-fn generate_results(numbers: &[u64], functions: &[fn(u64, u64) -> u64]) -> HashSet<u64> {
-    if numbers.len() < 2 {
-        panic!("The numbers array must contain at least 2 elements");
+/// Removes `--ops <list>` from the arguments
+fn take_operators(args: &mut Vec<String>) -> Result<Option<Vec<&'static dyn Operator>>, String> {
+    let Some(i) = args.iter().position(|arg| arg == "--ops") else {
+        return Ok(None);
+    };
+    if i + 1 >= args.len() {
+        return Err("Missing operator list after --ops (e.g. --ops +,*,||)".to_string());
     }
-    let mut results = HashSet::new();
-
-    let mut previous_numbers = vec![numbers[0]];
-    for i in 1..numbers.len() {
-        let mut next_previous_numbers = vec![];
-        for f in functions {
-            for previous_number in &previous_numbers {
-                next_previous_numbers.push(f(*previous_number, numbers[i]));
-                if i == numbers.len() - 1 {
-                    results.insert(f(*previous_number, numbers[i]));
-                }
-            }
-        }
-        previous_numbers = next_previous_numbers;
-    }
-    results
-}
-
-fn add(a: u64, b: u64) -> u64 {
-    a + b
-}
-
-fn mul(a: u64, b: u64) -> u64 {
-    a * b
-}
-
-fn concat(a: u64, b: u64) -> u64 {
-    format!("{}{}", a, b).parse().unwrap()
+    let operators = parse_operators(&args.remove(i + 1))?;
+    args.remove(i);
+    Ok(Some(operators))
 }
 
-fn calibration_result(hmap: &HashMap<u64, Vec<u64>>, functions: &[fn(u64, u64) -> u64],) -> u64 {
-    let mut result = 0;
-    for (key, values) in hmap {
-        let results = generate_results(values, functions);
-        if results.contains(&key) {
-            result += key;
-        }
+/// The total, or a hint when it does not fit in a `Value`
+fn format_result(result: Option<Value>) -> String {
+    match result {
+        Some(result) => result.to_string(),
+        None => "too large (try the u128 feature)".to_string(),
     }
-    result
 }
 
 fn main() {
-    let hmap = parse_file_to_hmap("inputs\\input.txt").unwrap();
-    let result = calibration_result(&hmap, &[add, mul]);
-    println!("The calibration result is: {}", result);
-    let result = calibration_result(&hmap, &[add, mul, concat]);
-    println!("The calibration result with concat is: {}", result);
-
-}
-#[cfg(test)]
-mod tests {
-    use super::*;
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let selected = match take_operators(&mut args) {
+        Ok(selected) => selected,
+        Err(e) => return eprintln!("{}", e),
+    };
+    // Lines that cannot be read are reported and left out of the results
+    let report = match parse_file("inputs/input.txt") {
+        Ok(report) => report,
+        Err(e) => return eprintln!("Failed to read inputs/input.txt: {}", e),
+    };
+    eprint!("{}", report);
+    let equations = report.equations;
+    let operators = selected.clone().unwrap_or_else(|| vec![&Add, &Mul, &Concat]);
 
-    #[test]
-    fn test_generate_results() {
-        let numbers = vec![1, 2, 3, 4];
-        let functions = vec![add, mul];
-        let results = generate_results(&numbers, &functions);
-        let expected_results: HashSet<u64> = [10, 9, 13, 24, 20, 36].iter().cloned().collect();
-        assert_eq!(results, expected_results);
-    }
-
-    #[test]
-    fn test_calibration_result() {
-        let hmap = parse_file_to_hmap("inputs\\test.txt").unwrap();
-        let result = calibration_result(&hmap, &[add, mul]);
-        assert_eq!(result, 3749);
+    // `audit [--all]`: operators used on each line; `csv [--all]`: the same as CSV;
+    // `compare [--parens]`: totals left to right and with precedence (and with any parentheses,
+    // only practical for short equations)
+    let all = args.iter().any(|arg| arg == "--all");
+    let parens = args.iter().any(|arg| arg == "--parens");
+    match args.first().map(String::as_str) {
+        Some("audit") => {
+            for audit in audit(&equations, &operators, all) {
+                print!("{}", audit);
+            }
+        }
+        Some("csv") => print!("{}", export_csv(&audit(&equations, &operators, all))),
+        Some("compare") => {
+            for evaluation in Evaluation::ALL {
+                if evaluation != Evaluation::Parentheses || parens {
+                    let result = evaluation.calibration_result(&equations, &operators);
+                    println!("The calibration result {} is: {}", evaluation.name(), format_result(result));
+                }
+            }
+        }
+        _ if selected.is_some() => {
+            let symbols: Vec<&str> = operators.iter().map(|operator| operator.symbol()).collect();
+            let result = calibration_result(&equations, &operators);
+            println!("The calibration result with {} is: {}", symbols.join(" "), format_result(result));
+        }
+        _ => {
+            let result = calibration_result(&equations, &[&Add, &Mul]);
+            println!("The calibration result is: {}", format_result(result));
+            let result = calibration_result(&equations, &operators);
+            println!("The calibration result with concat is: {}", format_result(result));
+        }
     }
 }
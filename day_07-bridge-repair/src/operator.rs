@@ -0,0 +1,274 @@
+use std::fmt;
+
+/// Calibration values: `u128` with the `u128` feature, for very large targets
+#[cfg(not(feature = "u128"))]
+pub type Value = u64;
+#[cfg(feature = "u128")]
+pub type Value = u128;
+
+/// What the left operand must be for an operator to give a result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preimage {
+    Impossible,
+    Exactly(Value),
+    /// Any value works, e.g. `a * 0 == 0`
+    Anything,
+}
+
+/// `inverse(result, b)` tells which `a` satisfies `apply(a, b) == result`
+pub type Inverse = fn(Value, Value) -> Preimage;
+
+pub trait Operator {
+    /// Name accepted on the command line, e.g. `add`
+    fn name(&self) -> &'static str;
+
+    /// Symbol used to print expressions, also accepted on the command line
+    fn symbol(&self) -> &'static str;
+
+    /// None when the result overflows or is not defined
+    fn apply(&self, a: Value, b: Value) -> Option<Value>;
+
+    /// Operators without an inverse can only be searched left to right
+    fn inverse(&self) -> Option<Inverse> {
+        None
+    }
+
+    /// Binding strength when evaluating with precedence: higher binds tighter
+    fn precedence(&self) -> u8;
+
+    /// `a ^ b ^ c` reads as `a ^ (b ^ c)`
+    fn is_right_associative(&self) -> bool {
+        false
+    }
+
+    /// True when `apply(a, b) >= max(a, b)` for all `a, b >= 1`, so that a partial result
+    /// above the target can be dropped
+    fn is_increasing(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Debug for dyn Operator + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl PartialEq for dyn Operator + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+pub struct Add;
+pub struct Mul;
+pub struct Concat;
+pub struct Sub;
+pub struct Pow;
+
+impl Operator for Add {
+    fn name(&self) -> &'static str {
+        "add"
+    }
+
+    fn symbol(&self) -> &'static str {
+        "+"
+    }
+
+    fn apply(&self, a: Value, b: Value) -> Option<Value> {
+        a.checked_add(b)
+    }
+
+    fn inverse(&self) -> Option<Inverse> {
+        Some(|result, b| result.checked_sub(b).map_or(Preimage::Impossible, Preimage::Exactly))
+    }
+
+    fn precedence(&self) -> u8 {
+        1
+    }
+
+    fn is_increasing(&self) -> bool {
+        true
+    }
+}
+
+impl Operator for Mul {
+    fn name(&self) -> &'static str {
+        "mul"
+    }
+
+    fn symbol(&self) -> &'static str {
+        "*"
+    }
+
+    fn apply(&self, a: Value, b: Value) -> Option<Value> {
+        a.checked_mul(b)
+    }
+
+    fn inverse(&self) -> Option<Inverse> {
+        Some(|result, b| match b {
+            0 if result == 0 => Preimage::Anything,
+            0 => Preimage::Impossible,
+            _ if result.is_multiple_of(b) => Preimage::Exactly(result / b),
+            _ => Preimage::Impossible,
+        })
+    }
+
+    fn precedence(&self) -> u8 {
+        2
+    }
+
+    fn is_increasing(&self) -> bool {
+        true
+    }
+}
+
+fn digits(n: Value) -> u32 {
+    n.checked_ilog10().unwrap_or(0) + 1
+}
+
+/// Smallest power of ten greater than `n`, i.e. the shift applied by `a || n`
+fn shift(n: Value) -> Option<Value> {
+    Value::checked_pow(10, digits(n))
+}
+
+impl Operator for Concat {
+    fn name(&self) -> &'static str {
+        "concat"
+    }
+
+    fn symbol(&self) -> &'static str {
+        "||"
+    }
+
+    fn apply(&self, a: Value, b: Value) -> Option<Value> {
+        a.checked_mul(shift(b)?)?.checked_add(b)
+    }
+
+    fn inverse(&self) -> Option<Inverse> {
+        Some(|result, b| match shift(b) {
+            Some(power) if result % power == b => Preimage::Exactly(result / power),
+            _ => Preimage::Impossible,
+        })
+    }
+
+    /// Digits are joined before any arithmetic
+    fn precedence(&self) -> u8 {
+        4
+    }
+
+    fn is_increasing(&self) -> bool {
+        true
+    }
+}
+
+impl Operator for Sub {
+    fn name(&self) -> &'static str {
+        "sub"
+    }
+
+    fn symbol(&self) -> &'static str {
+        "-"
+    }
+
+    /// Negative results are not allowed
+    fn apply(&self, a: Value, b: Value) -> Option<Value> {
+        a.checked_sub(b)
+    }
+
+    fn inverse(&self) -> Option<Inverse> {
+        Some(|result, b| result.checked_add(b).map_or(Preimage::Impossible, Preimage::Exactly))
+    }
+
+    fn precedence(&self) -> u8 {
+        1
+    }
+}
+
+impl Operator for Pow {
+    fn name(&self) -> &'static str {
+        "pow"
+    }
+
+    fn symbol(&self) -> &'static str {
+        "^"
+    }
+
+    fn apply(&self, a: Value, b: Value) -> Option<Value> {
+        a.checked_pow(u32::try_from(b).ok()?)
+    }
+
+    fn precedence(&self) -> u8 {
+        3
+    }
+
+    fn is_right_associative(&self) -> bool {
+        true
+    }
+}
+
+/// Every known operator
+pub const OPERATORS: [&dyn Operator; 5] = [&Add, &Mul, &Concat, &Sub, &Pow];
+
+pub fn find_operator(name: &str) -> Option<&'static dyn Operator> {
+    OPERATORS.into_iter().find(|operator| operator.name() == name || operator.symbol() == name)
+}
+
+/// Reads a comma-separated list of operator names or symbols, e.g. `+,*,||`
+pub fn parse_operators(list: &str) -> Result<Vec<&'static dyn Operator>, String> {
+    list.split(',')
+        .map(|name| {
+            let name = name.trim();
+            find_operator(name).ok_or_else(|| {
+                let known: Vec<&str> = OPERATORS.iter().map(|operator| operator.symbol()).collect();
+                format!("Unknown operator: {:?} (expected one of {})", name, known.join(" "))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(Add.apply(Value::MAX, 1), None);
+        assert_eq!(Mul.apply(6, 7), Some(42));
+        assert_eq!(Concat.apply(15, 6), Some(156));
+        assert_eq!(Concat.apply(Value::MAX, 1), None);
+        assert_eq!(Concat.apply(Value::MAX / 10, 9), None);
+        assert_eq!(Concat.apply(Value::MAX / 100, 9), Some(Value::MAX / 100 * 10 + 9));
+        assert_eq!(Concat.apply(0, 0), Some(0));
+        assert_eq!(Concat.apply(12, 0), Some(120));
+        assert_eq!(Concat.apply(1, 100), Some(1100));
+        assert_eq!(Sub.apply(3, 5), None);
+        assert_eq!(Pow.apply(2, 10), Some(1024));
+        assert_eq!(Pow.apply(2, 200), None);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let undo = |operator: &dyn Operator, result, b| operator.inverse().unwrap()(result, b);
+        assert_eq!(undo(&Add, 10, 4), Preimage::Exactly(6));
+        assert_eq!(undo(&Add, 3, 4), Preimage::Impossible);
+        assert_eq!(undo(&Mul, 12, 4), Preimage::Exactly(3));
+        assert_eq!(undo(&Mul, 13, 4), Preimage::Impossible);
+        assert_eq!(undo(&Mul, 0, 0), Preimage::Anything);
+        assert_eq!(undo(&Mul, 5, 0), Preimage::Impossible);
+        assert_eq!(undo(&Concat, 156, 6), Preimage::Exactly(15));
+        assert_eq!(undo(&Concat, 1510, 10), Preimage::Exactly(15));
+        assert_eq!(undo(&Concat, 156, 56), Preimage::Exactly(1));
+        assert_eq!(undo(&Concat, 157, 6), Preimage::Impossible);
+        assert_eq!(undo(&Sub, 3, 4), Preimage::Exactly(7));
+        assert!(Pow.inverse().is_none());
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        let operators = parse_operators("+, mul,||,-,^").unwrap();
+        let symbols: Vec<&str> = operators.iter().map(|operator| operator.symbol()).collect();
+        assert_eq!(symbols, vec!["+", "*", "||", "-", "^"]);
+        assert_eq!(parse_operators("+,%").unwrap_err(), "Unknown operator: \"%\" (expected one of + * || - ^)");
+    }
+}
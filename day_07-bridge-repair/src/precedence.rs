@@ -0,0 +1,272 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::equation::Equation;
+use crate::operator::{Operator, Value};
+use crate::solver::{format_expression, solve};
+
+/// How an expression such as `81 + 40 * 27` is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Evaluation {
+    /// Strictly left to right, as in the puzzle: `(81 + 40) * 27`
+    LeftToRight,
+    /// Standard precedence: `81 + (40 * 27)`
+    Precedence,
+    /// Any parentheses may be inserted between the operands
+    Parentheses,
+}
+
+impl FromStr for Evaluation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(Evaluation::LeftToRight),
+            "precedence" => Ok(Evaluation::Precedence),
+            "parens" => Ok(Evaluation::Parentheses),
+            _ => Err(format!("Unknown evaluation: {:?} (expected left, precedence or parens)", s)),
+        }
+    }
+}
+
+impl Evaluation {
+    pub const ALL: [Evaluation; 3] = [Evaluation::LeftToRight, Evaluation::Precedence, Evaluation::Parentheses];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Evaluation::LeftToRight => "left to right",
+            Evaluation::Precedence => "with precedence",
+            Evaluation::Parentheses => "with parentheses",
+        }
+    }
+
+    /// An expression that turns `numbers` into `target` under this evaluation
+    pub fn solve(self, target: Value, numbers: &[Value], operators: &[&dyn Operator]) -> Option<String> {
+        match self {
+            Evaluation::LeftToRight => solve(target, numbers, operators).map(|solution| format_expression(numbers, &solution)),
+            Evaluation::Precedence => solve_with_precedence(target, numbers, operators).map(|solution| format_expression(numbers, &solution)),
+            Evaluation::Parentheses => solve_with_parentheses(target, numbers, operators),
+        }
+    }
+
+    /// Sum of the targets that can be reached, or None if the sum overflows
+    pub fn calibration_result(self, equations: &[Equation], operators: &[&dyn Operator]) -> Option<Value> {
+        let mut result: Value = 0;
+        for equation in equations {
+            if self.solve(equation.target, &equation.numbers, operators).is_some() {
+                result = result.checked_add(equation.target)?;
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Left operands waiting for an operator that binds tighter to be applied first
+type Pending<'a> = Vec<(Value, &'a dyn Operator)>;
+
+/// Applies the pending operators that bind at least as tightly as `next`, or all of them
+/// when `next` is None. None when a result overflows.
+fn reduce(pending: &mut Pending, mut value: Value, next: Option<&dyn Operator>) -> Option<Value> {
+    while let Some(&(left, operator)) = pending.last() {
+        let binds = next.is_none_or(|next| {
+            operator.precedence() > next.precedence()
+                || operator.precedence() == next.precedence() && !next.is_right_associative()
+        });
+        if !binds {
+            break;
+        }
+        value = operator.apply(left, value)?;
+        pending.pop();
+    }
+    Some(value)
+}
+
+/// `numbers` joined by the operators, evaluated with precedence
+pub fn evaluate_with_precedence(numbers: &[Value], operators: &[&dyn Operator]) -> Option<Value> {
+    let (&first, rest) = numbers.split_first()?;
+    let mut pending = Pending::new();
+    let mut value = first;
+    for (&operator, &number) in operators.iter().zip(rest) {
+        let left = reduce(&mut pending, value, Some(operator))?;
+        pending.push((left, operator));
+        value = number;
+    }
+    reduce(&mut pending, value, None)
+}
+
+/// Tries every operator from left to right, keeping the operands that cannot be combined yet.
+/// `bound` is the target when no partial result may exceed it.
+fn search_with_precedence<'a>(
+    pending: &Pending<'a>,
+    value: Value,
+    numbers: &[Value],
+    operators: &[&'a dyn Operator],
+    target: Value,
+    bound: Option<Value>,
+    chosen: &mut Vec<&'a dyn Operator>,
+) -> bool {
+    if bound.is_some_and(|bound| value > bound) {
+        return false;
+    }
+    let Some((&next, rest)) = numbers.split_first() else {
+        return reduce(&mut pending.clone(), value, None) == Some(target);
+    };
+    operators.iter().any(|&operator| {
+        let mut pending = pending.clone();
+        let Some(left) = reduce(&mut pending, value, Some(operator)) else {
+            return false;
+        };
+        if bound.is_some_and(|bound| left > bound) {
+            return false;
+        }
+        pending.push((left, operator));
+        chosen.push(operator);
+        if search_with_precedence(&pending, next, rest, operators, target, bound, chosen) {
+            return true;
+        }
+        chosen.pop();
+        false
+    })
+}
+
+/// Without zeros, increasing operators never give a partial result above the final one
+fn bound(target: Value, numbers: &[Value], operators: &[&dyn Operator]) -> Option<Value> {
+    let increasing = operators.iter().all(|operator| operator.is_increasing());
+    (increasing && numbers.iter().all(|&number| number >= 1)).then_some(target)
+}
+
+/// First operator sequence found that turns `numbers` into `target`, evaluated with precedence
+pub fn solve_with_precedence<'a>(
+    target: Value,
+    numbers: &[Value],
+    operators: &[&'a dyn Operator],
+) -> Option<Vec<&'a dyn Operator>> {
+    let (&first, rest) = numbers.split_first()?;
+    let mut chosen = Vec::new();
+    let bound = bound(target, numbers, operators);
+    search_with_precedence(&Pending::new(), first, rest, operators, target, bound, &mut chosen).then_some(chosen)
+}
+
+/// How a value was reached on a range of operands: the split point, the operator
+/// and the values of both sides. None for a single operand.
+type Origin<'a> = Option<(usize, &'a dyn Operator, Value, Value)>;
+
+/// Every value reachable on `numbers[start..end]`, for every range, with one way to reach it.
+/// The number of values grows quickly with the number of operands.
+fn reachable<'a>(numbers: &[Value], operators: &[&'a dyn Operator], bound: Option<Value>) -> Vec<Vec<BTreeMap<Value, Origin<'a>>>> {
+    let n = numbers.len();
+    let mut values: Vec<Vec<BTreeMap<Value, Origin>>> = vec![vec![BTreeMap::new(); n + 1]; n];
+    for (start, &number) in numbers.iter().enumerate() {
+        values[start][start + 1].insert(number, None);
+    }
+    for length in 2..=n {
+        for start in 0..=n - length {
+            let end = start + length;
+            let mut range = BTreeMap::new();
+            for (split, left) in values[start].iter().enumerate().take(end).skip(start + 1) {
+                for &a in left.keys() {
+                    for &b in values[split][end].keys() {
+                        for &operator in operators {
+                            let Some(result) = operator.apply(a, b) else {
+                                continue;
+                            };
+                            if bound.is_none_or(|bound| result <= bound) {
+                                range.entry(result).or_insert(Some((split, operator, a, b)));
+                            }
+                        }
+                    }
+                }
+            }
+            values[start][end] = range;
+        }
+    }
+    values
+}
+
+fn format_origin(values: &[Vec<BTreeMap<Value, Origin>>], start: usize, end: usize, value: Value, outer: bool) -> String {
+    match values[start][end][&value] {
+        None => value.to_string(),
+        Some((split, operator, a, b)) => {
+            let left = format_origin(values, start, split, a, false);
+            let right = format_origin(values, split, end, b, false);
+            let expression = format!("{} {} {}", left, operator.symbol(), right);
+            if outer { expression } else { format!("({})", expression) }
+        }
+    }
+}
+
+/// An expression with parentheses that turns `numbers` into `target`, e.g. `81 + (40 * 27)`.
+/// Time and memory grow exponentially with the number of operands: meant for short equations.
+pub fn solve_with_parentheses(target: Value, numbers: &[Value], operators: &[&dyn Operator]) -> Option<String> {
+    if numbers.is_empty() {
+        return None;
+    }
+    let values = reachable(numbers, operators, bound(target, numbers, operators));
+    values[0][numbers.len()]
+        .contains_key(&target)
+        .then(|| format_origin(&values, 0, numbers.len(), target, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::parse_file;
+    use crate::operator::{Add, Concat, Mul, Pow, Sub};
+
+    #[test]
+    fn test_evaluate_with_precedence() {
+        let evaluate = |numbers: &[Value], operators: &[&dyn Operator]| evaluate_with_precedence(numbers, operators);
+        assert_eq!(evaluate(&[81, 40, 27], &[&Add, &Mul]), Some(1161));
+        assert_eq!(evaluate(&[2, 3, 4, 5], &[&Mul, &Add, &Mul]), Some(26));
+        assert_eq!(evaluate(&[10, 3, 2], &[&Sub, &Sub]), Some(5));
+        assert_eq!(evaluate(&[2, 3, 2], &[&Pow, &Pow]), Some(512));
+        assert_eq!(evaluate(&[2, 1, 5, 3], &[&Mul, &Concat, &Add]), Some(33));
+        assert_eq!(evaluate(&[1, 2, 3], &[&Sub, &Add]), None);
+        assert_eq!(evaluate(&[7], &[]), Some(7));
+    }
+
+    #[test]
+    fn test_solve_with_precedence() {
+        let operators: [&dyn Operator; 3] = [&Add, &Mul, &Concat];
+        let solution = solve_with_precedence(1161, &[81, 40, 27], &operators).unwrap();
+        assert_eq!(format_expression(&[81, 40, 27], &solution), "81 + 40 * 27");
+        // Left to right, 81 + 40 * 27 is 3267; with precedence only 81 * 40 + 27 gives it
+        let solution = solve_with_precedence(3267, &[81, 40, 27], &operators).unwrap();
+        assert_eq!(format_expression(&[81, 40, 27], &solution), "81 * 40 + 27");
+
+        // Agrees with evaluating every operator sequence
+        let all: [&dyn Operator; 5] = [&Add, &Mul, &Concat, &Sub, &Pow];
+        let numbers = [3, 1, 2, 0, 2];
+        let mut reachable = Vec::new();
+        for code in 0..all.len().pow(4) {
+            let sequence: Vec<&dyn Operator> = (0..4).map(|i| all[code / all.len().pow(i) % all.len()]).collect();
+            reachable.extend(evaluate_with_precedence(&numbers, &sequence));
+        }
+        for target in 0..500 {
+            assert_eq!(solve_with_precedence(target, &numbers, &all).is_some(), reachable.contains(&target), "{}", target);
+        }
+    }
+
+    #[test]
+    fn test_solve_with_parentheses() {
+        let operators: [&dyn Operator; 2] = [&Add, &Mul];
+        assert_eq!(solve_with_parentheses(1161, &[81, 40, 27], &operators).unwrap(), "81 + (40 * 27)");
+        assert_eq!(solve_with_parentheses(3267, &[81, 40, 27], &operators).unwrap(), "(81 + 40) * 27");
+        assert_eq!(solve_with_parentheses(14, &[2, 3, 4], &operators).unwrap(), "2 * (3 + 4)");
+        assert_eq!(solve_with_parentheses(21, &[2, 3, 4], &operators), None);
+    }
+
+    #[test]
+    fn test_calibration_results() {
+        let equations = parse_file("inputs/test.txt").unwrap().equations;
+        let operators: [&dyn Operator; 2] = [&Add, &Mul];
+        let results: Vec<Option<Value>> = Evaluation::ALL
+            .iter()
+            .map(|evaluation| evaluation.calibration_result(&equations, &operators))
+            .collect();
+        // 292: 11 6 16 20 is 11 + 6 * 16 + 20 only when read left to right
+        assert_eq!(results, vec![Some(3749), Some(3457), Some(3749)]);
+        assert_eq!("precedence".parse(), Ok(Evaluation::Precedence));
+        assert!("right".parse::<Evaluation>().is_err());
+    }
+}
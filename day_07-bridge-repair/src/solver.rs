@@ -0,0 +1,262 @@
+use std::collections::HashSet;
+
+use crate::equation::Equation;
+use crate::operator::{Operator, Preimage, Value};
+
+/// Every value reachable from left to right. A single number only gives itself.
+pub fn generate_results(numbers: &[Value], operators: &[&dyn Operator]) -> HashSet<Value> {
+    let Some((&first, rest)) = numbers.split_first() else {
+        return HashSet::new();
+    };
+    let mut previous_numbers = vec![first];
+    for &number in rest {
+        let mut next_previous_numbers = vec![];
+        for operator in operators {
+            for previous_number in &previous_numbers {
+                if let Some(result) = operator.apply(*previous_number, number) {
+                    next_previous_numbers.push(result);
+                }
+            }
+        }
+        previous_numbers = next_previous_numbers;
+    }
+    previous_numbers.into_iter().collect()
+}
+
+/// Tries every operator from left to right. `chosen` holds the operators picked so far.
+/// Returns true to stop the search.
+fn search_forward<'a>(
+    value: Value,
+    numbers: &[Value],
+    operators: &[&'a dyn Operator],
+    need: Preimage,
+    chosen: &mut Vec<&'a dyn Operator>,
+    on_solution: &mut dyn FnMut(&[&'a dyn Operator]) -> bool,
+) -> bool {
+    let Some((&next, rest)) = numbers.split_first() else {
+        let found = match need {
+            Preimage::Exactly(target) => value == target,
+            Preimage::Anything => true,
+            Preimage::Impossible => false,
+        };
+        return found && on_solution(chosen);
+    };
+    operators.iter().any(|&operator| {
+        let Some(value) = operator.apply(value, next) else {
+            return false;
+        };
+        chosen.push(operator);
+        let stop = search_forward(value, rest, operators, need, chosen, on_solution);
+        chosen.pop();
+        stop
+    })
+}
+
+/// Works backwards from the target: the last operand is undone with each operator's inverse
+/// (subtraction, exact division, suffix stripping...) and a branch is dropped as soon as
+/// it cannot be undone. `chosen` holds the operators picked so far, from right to left.
+/// Returns true to stop the search.
+fn search_backward<'a>(
+    need: Preimage,
+    numbers: &[Value],
+    operators: &[&'a dyn Operator],
+    chosen: &mut Vec<&'a dyn Operator>,
+    on_solution: &mut dyn FnMut(&[&'a dyn Operator]) -> bool,
+) -> bool {
+    match (need, numbers) {
+        (_, []) | (Preimage::Impossible, _) => false,
+        // Any value works for the numbers on the left, as long as it can be computed
+        (Preimage::Anything, [first, rest @ ..]) => {
+            let right: Vec<&dyn Operator> = chosen.iter().rev().copied().collect();
+            search_forward(*first, rest, operators, need, &mut Vec::new(), &mut |left| {
+                on_solution(&[left, &right[..]].concat())
+            })
+        }
+        (Preimage::Exactly(target), [first]) => {
+            target == *first && on_solution(&chosen.iter().rev().copied().collect::<Vec<_>>())
+        }
+        (Preimage::Exactly(target), [rest @ .., last]) => operators.iter().any(|&operator| {
+            let Some(inverse) = operator.inverse() else {
+                return false;
+            };
+            chosen.push(operator);
+            let stop = search_backward(inverse(target, *last), rest, operators, chosen, on_solution);
+            chosen.pop();
+            stop
+        }),
+    }
+}
+
+/// Backward search when every operator can be inverted, forward search otherwise
+fn search<'a>(
+    target: Value,
+    numbers: &[Value],
+    operators: &[&'a dyn Operator],
+    on_solution: &mut dyn FnMut(&[&'a dyn Operator]) -> bool,
+) {
+    let need = Preimage::Exactly(target);
+    if operators.iter().all(|operator| operator.inverse().is_some()) {
+        search_backward(need, numbers, operators, &mut Vec::new(), on_solution);
+    } else if let Some((&first, rest)) = numbers.split_first() {
+        search_forward(first, rest, operators, need, &mut Vec::new(), on_solution);
+    }
+}
+
+/// First operator sequence found that turns `numbers` into `target`, from left to right
+pub fn solve<'a>(target: Value, numbers: &[Value], operators: &[&'a dyn Operator]) -> Option<Vec<&'a dyn Operator>> {
+    let mut found = None;
+    search(target, numbers, operators, &mut |solution| {
+        found = Some(solution.to_vec());
+        true
+    });
+    found
+}
+
+/// Every operator sequence that turns `numbers` into `target`
+pub fn solve_all<'a>(target: Value, numbers: &[Value], operators: &[&'a dyn Operator]) -> Vec<Vec<&'a dyn Operator>> {
+    let mut solutions = Vec::new();
+    search(target, numbers, operators, &mut |solution| {
+        solutions.push(solution.to_vec());
+        false
+    });
+    solutions
+}
+
+pub fn is_solvable(target: Value, numbers: &[Value], operators: &[&dyn Operator]) -> bool {
+    solve(target, numbers, operators).is_some()
+}
+
+/// `numbers` joined by the operators, e.g. `81 + 40 * 27`
+pub fn format_expression(numbers: &[Value], operators: &[&dyn Operator]) -> String {
+    let mut expression = numbers.first().map(Value::to_string).unwrap_or_default();
+    for (operator, number) in operators.iter().zip(&numbers[1..]) {
+        expression.push_str(&format!(" {} {}", operator.symbol(), number));
+    }
+    expression
+}
+
+/// Sum of the targets that can be reached, or None if the sum overflows
+pub fn calibration_result(equations: &[Equation], operators: &[&dyn Operator]) -> Option<Value> {
+    let mut result: Value = 0;
+    for equation in equations {
+        if is_solvable(equation.target, &equation.numbers, operators) {
+            result = result.checked_add(equation.target)?;
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::{parse_equations, parse_file};
+    use crate::operator::{Add, Concat, Mul, Pow, Sub};
+
+    #[test]
+    fn test_generate_results() {
+        let numbers = vec![1, 2, 3, 4];
+        let operators = vec![&Add as &dyn Operator, &Mul];
+        let results = generate_results(&numbers, &operators);
+        let expected_results: HashSet<Value> = [10, 9, 13, 24, 20, 36].iter().cloned().collect();
+        assert_eq!(results, expected_results);
+    }
+
+    #[test]
+    fn test_calibration_result() {
+        let equations = parse_file("inputs/test.txt").unwrap().equations;
+        let result = calibration_result(&equations, &[&Add, &Mul]);
+        assert_eq!(result, Some(3749));
+        let result = calibration_result(&equations, &[&Add as &dyn Operator, &Mul, &Concat]);
+        assert_eq!(result, Some(11387));
+    }
+
+    #[test]
+    fn test_duplicate_targets() {
+        let equations = parse_equations("10: 5 5\n10: 2 5\n\n10: 3 3\n").equations;
+        assert_eq!(equations.len(), 3);
+        assert_eq!(equations[2], Equation { line: 4, target: 10, numbers: vec![3, 3] });
+        // The first two lines are both valid and must both be counted
+        assert_eq!(calibration_result(&equations, &[&Add, &Mul]), Some(20));
+    }
+
+    #[test]
+    fn test_single_operand() {
+        let operators: [&dyn Operator; 2] = [&Add, &Mul];
+        assert_eq!(generate_results(&[7], &operators), HashSet::from([7]));
+        assert!(generate_results(&[], &operators).is_empty());
+        assert!(is_solvable(7, &[7], &operators));
+        assert!(!is_solvable(8, &[7], &operators));
+        assert!(is_solvable(7, &[7], &[&Pow]));
+        assert_eq!(solve_all(7, &[7], &operators), vec![Vec::<&dyn Operator>::new()]);
+        let equations = parse_equations("7: 7\n8: 7\n15: 7 8\n").equations;
+        assert_eq!(calibration_result(&equations, &operators), Some(22));
+
+        // The total itself may not fit
+        let equations = parse_equations(&format!("{0}: {0}\n1: 1\n", Value::MAX)).equations;
+        assert_eq!(calibration_result(&equations, &operators), None);
+    }
+
+    #[test]
+    fn test_is_solvable_matches_generate_results() {
+        let operators = [&Add as &dyn Operator, &Mul, &Concat];
+        let mut seed: Value = 7;
+        let mut next = |bound: Value| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+        for _ in 0..200 {
+            let numbers: Vec<Value> = (0..2 + next(4)).map(|_| next(12)).collect();
+            let results = generate_results(&numbers, &operators);
+            for target in 0..2000 {
+                assert_eq!(is_solvable(target, &numbers, &operators), results.contains(&target), "{:?} {}", numbers, target);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let operators: [&dyn Operator; 3] = [&Add, &Mul, &Concat];
+        let numbers = [81, 40, 27];
+        let solution = solve(3267, &numbers, &operators).unwrap();
+        assert_eq!(format_expression(&numbers, &solution), "81 * 40 + 27");
+        assert_eq!(solve(83, &[17, 5], &operators), None);
+
+        let mut expressions: Vec<String> = solve_all(3267, &numbers, &operators)
+            .iter()
+            .map(|solution| format_expression(&numbers, solution))
+            .collect();
+        expressions.sort();
+        assert_eq!(expressions, vec!["81 * 40 + 27", "81 + 40 * 27"]);
+        assert_eq!(format_expression(&[7290], &[]), "7290");
+
+        // Multiplying by zero makes every operator on the left valid
+        assert_eq!(solve_all(0, &[4, 2, 0], &[&Add, &Mul]).len(), 2);
+        // ...as long as the left part can be computed: 2 - 4 is negative
+        assert_eq!(solve_all(0, &[2, 4, 0], &[&Sub, &Mul]).len(), 1);
+    }
+
+    #[test]
+    fn test_operators_without_inverse() {
+        let operators: [&dyn Operator; 3] = [&Add, &Sub, &Pow];
+        let numbers = [2, 3, 1, 4];
+        let solution = solve(6561, &numbers, &operators).unwrap();
+        assert_eq!(format_expression(&numbers, &solution), "2 ^ 3 + 1 ^ 4");
+        let results = generate_results(&numbers, &operators);
+        for target in 0..10_000 {
+            assert_eq!(is_solvable(target, &numbers, &operators), results.contains(&target));
+        }
+        // Backward search with subtraction agrees with full enumeration
+        let results = generate_results(&numbers, &[&Add, &Sub, &Mul]);
+        for target in 0..100 {
+            assert_eq!(is_solvable(target, &numbers, &[&Add, &Sub, &Mul]), results.contains(&target));
+        }
+    }
+
+    #[cfg(feature = "u128")]
+    #[test]
+    fn test_large_values() {
+        // u64::MAX || 0 does not fit in a u64
+        let equations = parse_equations("184467440737095516150: 18446744073709551615 0\n").equations;
+        assert_eq!(calibration_result(&equations, &[&Mul, &Concat]), Some(184467440737095516150));
+    }
+}